@@ -1,112 +1,78 @@
-advent_of_code::solution!(12);
+advent_of_code::solution!(12, normalized);
 
-use cached::proc_macro::cached;
+use advent_of_code::parse::{self, Line, ParseError};
 
-pub fn split_line(input: &str) -> (String, Vec<usize>) {
-    let mut lines = input.split(' ');
-    let first = lines.next().unwrap();
-
-    let mut result = String::new();
-    let mut prev_char = '\0';
-
-    for char in first.chars() {
-        if char != '.' || prev_char != '.' {
-            result.push(char);
-        }
-        prev_char = char;
-    }
-
-    let second = lines.next().unwrap();
-    let numbers: Result<Vec<usize>, _> = second.split(',').map(|s| s.parse::<usize>()).collect();
-    match numbers {
-        Ok(nums) => (result, nums),
-        Err(_e) => (result, vec![]),
+/// Splits a line such as `???.### 1,1,3` into its pattern and its group sizes.
+pub fn split_line<'a>(line: &Line<'a>) -> Result<(&'a str, Vec<usize>), ParseError> {
+    let (pattern, groups) = line.text.split_once(' ').ok_or_else(|| line.end_of_line("' ' then the group sizes"))?;
+    if let Some((index, c)) = pattern.char_indices().find(|(_, c)| !matches!(c, '.' | '#' | '?')) {
+        return Err(line.unexpected(&pattern[index..index + c.len_utf8()]));
     }
+    let groups = groups.split(',').map(|group| line.parse(group)).collect::<Result<_, _>>()?;
+    Ok((pattern, groups))
 }
 
-#[cached]
-pub fn count_match(pattern: String, list: Vec<usize>) -> usize {
-    let pattern = pattern.trim_start_matches('.');
-    if list.is_empty() {
-        if pattern.contains('#') {
-            return 0;
-        }
-        return 1;
-    } else if pattern.is_empty() {
-        return 0;
-    }
-
-    if list.iter().sum::<usize>() + (list.len() - 1) > pattern.len() {
-        return 0;
-    }
-    let first = list[0];
-    let rest = &list[1..];
-    if pattern.starts_with('#') {
-        let chars = pattern
-            .chars()
-            .take_while(|&c| c == '#' || c == '?')
-            .count();
-        if chars < first {
-            return 0;
-        }
-        let mut nouveau_texte: String = pattern.chars().skip(first).collect();
-        if nouveau_texte.is_empty() {
-            if rest.is_empty() {
-                return 1;
-            } else {
-                return 0;
+/// Counts the arrangements of `list` groups in `pattern`.
+///
+/// `table[i][j]` holds the number of ways to place the groups `list[j..]` in `pattern[i..]`,
+/// filled from the end of the pattern. `run[i]` is the number of cells from `i` that could be
+/// damaged, so checking whether a group fits is a single lookup.
+pub fn count_match(pattern: &[u8], list: &[usize]) -> usize {
+    let n = pattern.len();
+    let m = list.len();
+    let width = m + 1;
+    let mut run = vec![0; n + 1];
+    for i in (0..n).rev() {
+        run[i] = if pattern[i] == b'.' { 0 } else { run[i + 1] + 1 };
+    }
+    let mut table = vec![0; (n + 1) * width];
+    table[n * width + m] = 1;
+    for i in (0..n).rev() {
+        for j in 0..=m {
+            let mut ways = 0;
+            if pattern[i] != b'#' {
+                ways += table[(i + 1) * width + j];
+            }
+            if pattern[i] != b'.' && j < m {
+                let end = i + list[j];
+                if run[i] >= list[j] && (end == n || pattern[end] != b'#') {
+                    ways += table[(end + 1).min(n) * width + j + 1];
+                }
             }
-        } else if nouveau_texte.starts_with('#') {
-            return 0;
+            table[i * width + j] = ways;
         }
-        nouveau_texte = nouveau_texte.chars().skip(1).collect();
-        count_match(nouveau_texte, rest.to_vec())
-    } else {
-        let mut text1 = String::from(pattern);
-        text1.replace_range(0..1, "#");
-        let mut text2 = String::from(pattern);
-        text2.replace_range(0..1, ".");
-        let c1 = count_match(text1, list.clone());
-        let c2 = count_match(text2, list);
-        c1 + c2
     }
+    table[0]
 }
 
-fn multicount_match(line: &str, count: i32) -> usize {
-    let (pattern, list) = split_line(line);
-    let mut repeated_list = Vec::new();
-    for _ in 0..count {
-        for item in &list {
-            repeated_list.push(*item);
+fn multicount_match(pattern: &str, list: &[usize], count: usize) -> usize {
+    let repeated_list = list.repeat(count);
+    let mut repeated_pattern = Vec::with_capacity((pattern.len() + 1) * count);
+    for i in 0..count {
+        if i > 0 {
+            repeated_pattern.push(b'?');
         }
+        repeated_pattern.extend_from_slice(pattern.as_bytes());
     }
-    let mut repeated_pattern = pattern.clone();
-    for _ in 1..count {
-        repeated_pattern += "?";
-        repeated_pattern += pattern.as_str();
-    }
-    count_match(repeated_pattern, repeated_list)
+    count_match(&repeated_pattern, &repeated_list)
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
     let mut result = 0;
-    let lines = input.split('\n');
-    for line in lines {
-        let line_clone = line.to_string();
-        let (pattern, list) = split_line(&line_clone);
-        result += count_match(pattern, list);
+    for line in parse::lines(input) {
+        let (pattern, list) = split_line(&line)?;
+        result += count_match(pattern.as_bytes(), &list);
     }
-    Some(result)
+    Ok(result)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
     let mut result = 0;
-    let lines = input.split('\n');
-    for line in lines {
-        let line_clone = line.to_string();
-        result += multicount_match(&line_clone, 5)
+    for line in parse::lines(input) {
+        let (pattern, list) = split_line(&line)?;
+        result += multicount_match(pattern, &list, 5)
     }
-    Some(result)
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -115,49 +81,65 @@ mod tests {
 
     #[test]
     fn test_count_match1() {
-        let result = count_match("#.###".to_string(), [1, 3].to_vec());
+        let result = count_match(b"#.###", &[1, 3]);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn test_count_match2() {
-        let result = count_match("???".to_string(), [1, 2].to_vec());
+        let result = count_match(b"???", &[1, 2]);
         assert_eq!(result, 0);
     }
 
     #[test]
     fn test_split_line3() {
-        let result = count_match(".???#?..???#??.?.".to_string(), vec![1, 4]);
+        let result = count_match(b".???#?..???#??.?.", &[1, 4]);
         assert_eq!(result, 3);
     }
 
     #[test]
     fn test_multicount_match0() {
-        let result = count_match("???.###????.###".to_string(), vec![1, 1, 3, 1, 1, 3]);
+        let result = count_match(b"???.###????.###", &[1, 1, 3, 1, 1, 3]);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn test_multicount_match1() {
-        let result = multicount_match("???.### 1,1,3", 2);
+        let result = multicount_match("???.###", &[1, 1, 3], 2);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn test_multicount_match2() {
-        let result = multicount_match("????.#...#... 4,1,1", 5);
+        let result = multicount_match("????.#...#...", &[4, 1, 1], 5);
         assert_eq!(result, 16);
     }
 
     #[test]
     fn test_multicount_match3() {
-        let result = multicount_match(".??..??...?##. 1,1,3", 5);
+        let result = multicount_match(".??..??...?##.", &[1, 1, 3], 5);
         assert_eq!(result, 16384);
     }
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(21));
+        assert_eq!(result, Ok(21));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(525152));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let result = part_one("???.### 1,1,3\n.??..??...?##. 1,x,3");
+        assert_eq!(result.map_err(|e| (e.line, e.column)), Err((2, 18)));
+        let result = part_one("???.###");
+        assert_eq!(result.map_err(|e| e.column), Err(8));
+        let result = part_two("??@.### 1,1,3");
+        assert_eq!(result.map_err(|e| e.message), Err("unexpected '@'".to_string()));
     }
 }
@@ -1,4 +1,5 @@
 use std::ops::BitXor;
+use advent_of_code::parse::{self, ParseError};
 advent_of_code::solution!(13);
 
 struct Block {
@@ -14,10 +15,6 @@ impl Block {
         }
     }
 
-    fn is_empty(&self) -> bool {
-        self.data.is_empty()
-    }
-
     fn push(&mut self, value: usize, strlen: usize) {
         self.data.push(value);
         if self.width == 0 {
@@ -46,26 +43,28 @@ impl Block {
     }
 }
 
-fn parse_file_content(input: &str) -> Vec<Block> {
+fn parse_file_content(input: &str) -> Result<Vec<Block>, ParseError> {
     let mut blocks = Vec::new();
-    let mut current_block = Block::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            if !current_block.is_empty() {
-                blocks.push(current_block);
-                current_block = Block::new();
+    for lines in parse::blocks(input) {
+        let mut block = Block::new();
+        for line in lines {
+            if line.text.len() > usize::BITS as usize {
+                return Err(line.error(line.text, format!("expected at most {} columns", usize::BITS)));
             }
-        } else {
-            current_block.push(usize::from_str_radix(line, 2).unwrap(), line.len());
+            let mut value = 0;
+            for (index, c) in line.text.char_indices() {
+                let bit = match c {
+                    '#' => 1,
+                    '.' => 0,
+                    _ => return Err(line.unexpected(&line.text[index..index + c.len_utf8()])),
+                };
+                value = value << 1 | bit;
+            }
+            block.push(value, line.text.len());
         }
+        blocks.push(block);
     }
-
-    if !current_block.is_empty() {
-        blocks.push(current_block);
-    }
-
-    blocks
+    Ok(blocks)
 }
 
 fn is_truncated_reverse_of_each_other(list1: &[usize], list2: &[usize]) -> bool {
@@ -93,16 +92,15 @@ fn find_mirror_start_index(block: &[usize], original_index: usize) -> usize {
     0
 }
 
-fn get_numeric_blocks(input: &str) -> (Vec<Block>, Vec<Block>) {
-    let binary_string = input.replace('#', "1").replace('.', "0");
-    let blocks = parse_file_content(binary_string.as_str());
+fn get_numeric_blocks(input: &str) -> Result<(Vec<Block>, Vec<Block>), ParseError> {
+    let blocks = parse_file_content(input)?;
     let v_blocks = blocks.iter().map(|b| b.transpose_block()).collect();
-    (blocks, v_blocks)
+    Ok((blocks, v_blocks))
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
     let mut result = 0;
-    let (h_blocks, v_blocks) = get_numeric_blocks(input);
+    let (h_blocks, v_blocks) = get_numeric_blocks(input)?;
     for i in 0..h_blocks.len() {
         let ret = find_mirror_start_index(&v_blocks[i].data, 0);
         if ret > 0 {
@@ -111,11 +109,11 @@ pub fn part_one(input: &str) -> Option<usize> {
             result += 100 * find_mirror_start_index(&h_blocks[i].data, 0);
         };
     }
-    Some(result)
+    Ok(result)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let (h_blocks, v_blocks) = get_numeric_blocks(input);
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let (h_blocks, v_blocks) = get_numeric_blocks(input)?;
     let mut result = 0;
     for i in 0..h_blocks.len() {
         let ret = iterate_on_block(&v_blocks[i]);
@@ -125,7 +123,7 @@ pub fn part_two(input: &str) -> Option<usize> {
             result += 100 * iterate_on_block(&h_blocks[i]);
         }
     }
-    Some(result)
+    Ok(result)
 }
 
 fn iterate_on_block(block: &Block) -> usize {
@@ -155,30 +153,36 @@ mod tests {
 
     #[test]
     fn parse_file_content_splits_blocks_correctly() {
-        let input = "11100\n11100\n\n11100\n11100";
-        let result = parse_file_content(input);
+        let input = "###..\n###..\n\n###..\n###..";
+        let result = parse_file_content(input).unwrap();
         assert_eq!(result[0].data, [28, 28]);
         assert_eq!(result[1].data, [28, 28]);
     }
 
     #[test]
     fn parse_file_content_handles_input_without_empty_lines() {
-        let input = "11100\n11100";
-        let result = parse_file_content(input);
+        let input = "###..\n###..";
+        let result = parse_file_content(input).unwrap();
         assert_eq!(result[0].data, [28, 28]);
     }
 
+    #[test]
+    fn parse_file_content_reports_the_original_text() {
+        let result = parse_file_content("#.#\n#1#");
+        assert_eq!(result.err(), Some(ParseError::unexpected(2, 2, '1')));
+    }
+
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(405));
+        assert_eq!(result, Ok(405));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(400));
+        assert_eq!(result, Ok(400));
     }
 
     #[test]
@@ -1,142 +1,178 @@
-advent_of_code::solution!(14);
-
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
-
-pub fn count_lines_to_bottom(grid: &Vec<Vec<char>>) -> usize {
-    let mut count = 0;
-    for row in (0..grid.len()).rev() {
-        for col in 0..grid[row].len() {
-            if grid[row][col] == 'O' {
-                count += 1;
-                let mut current_row = row + 1;
-                while current_row < grid.len() {
-                    count += 1;
-                    current_row += 1;
+advent_of_code::solution!(14, visualize = Tilting);
+
+use std::collections::HashMap;
+use advent_of_code::template::visualize::{Color, Frame, Visualize};
+
+const CYCLES: usize = 1000000000;
+const VISUALIZED_CYCLES: usize = 3;
+
+/// A tilt of the platform, named after its direction.
+type Tilt = (&'static str, fn(&mut Platform));
+
+/// The tilts of a spin cycle, in order.
+const SPIN: [Tilt; 4] = [
+    ("north", Platform::tilt_north),
+    ("west", Platform::tilt_west),
+    ("south", Platform::tilt_south),
+    ("east", Platform::tilt_east),
+];
+
+/// Rocks packed as one `u128` per row, bit `c` standing for column `c`.
+struct Platform {
+    rows: usize,
+    mask: u128,
+    cubes: Vec<u128>,
+    rounds: Vec<u128>,
+}
+
+impl Platform {
+    /// Returns `None` if the platform is wider than 128 columns.
+    fn new(input: &str) -> Option<Self> {
+        let mut cols = 0;
+        let mut cubes = Vec::new();
+        let mut rounds = Vec::new();
+        for line in input.lines() {
+            if line.len() > u128::BITS as usize {
+                return None;
+            }
+            cols = cols.max(line.len());
+            let (mut cube, mut round) = (0, 0);
+            for (col, c) in line.bytes().enumerate() {
+                match c {
+                    b'#' => cube |= 1 << col,
+                    b'O' => round |= 1 << col,
+                    _ => (),
                 }
             }
+            cubes.push(cube);
+            rounds.push(round);
         }
+        let mask = if cols == u128::BITS as usize { u128::MAX } else { (1 << cols) - 1 };
+        Some(Self { rows: rounds.len(), mask, cubes, rounds })
     }
-    count
-}
 
-pub fn move_up(mut grid: Vec<Vec<char>>) -> Vec<Vec<char>> {
-    for row in 0..grid.len() {
-        for col in 0..grid[row].len() {
-            if grid[row][col] == 'O' {
-                let mut new_row = row;
-                while new_row > 0 && grid[new_row - 1][col] == '.' {
-                    new_row -= 1;
-                }
-                if new_row != row {
-                    grid[new_row][col] = 'O';
-                    grid[row][col] = '.';
-                }
-            }
+    fn free(&self, row: usize) -> u128 {
+        !(self.rounds[row] | self.cubes[row]) & self.mask
+    }
+
+    /// Rolls every rock of `row` towards `row - 1` (north) or `row + 1` (south) as far as it goes,
+    /// assuming rows on that side are already settled.
+    fn roll_vertically(&mut self, row: usize, north: bool) {
+        let mut current = row;
+        let mut moving = self.rounds[row];
+        while moving != 0 && (if north { current > 0 } else { current + 1 < self.rows }) {
+            let next = if north { current - 1 } else { current + 1 };
+            moving &= self.free(next);
+            self.rounds[current] &= !moving;
+            self.rounds[next] |= moving;
+            current = next;
         }
     }
-    grid
-}
 
-pub fn move_left(mut grid: Vec<Vec<char>>) -> Vec<Vec<char>> {
-    for row in 0..grid.len() {
-        for col in 0..grid[row].len() {
-            if grid[row][col] == 'O' {
-                let mut new_col = col;
-                while new_col > 0 && grid[row][new_col - 1] == '.' {
-                    new_col -= 1;
-                }
-                if new_col != col {
-                    grid[row][new_col] = 'O';
-                    grid[row][col] = '.';
-                }
-            }
+    fn tilt_north(&mut self) {
+        for row in 1..self.rows {
+            self.roll_vertically(row, true);
         }
     }
-    grid
-}
 
-pub fn move_right(mut grid: Vec<Vec<char>>) -> Vec<Vec<char>> {
-    for row in 0..grid.len() {
-        for col in (0..grid[row].len()).rev() {
-            if grid[row][col] == 'O' {
-                let mut new_col = col;
-                while new_col < grid[row].len() - 1 && grid[row][new_col + 1] == '.' {
-                    new_col += 1;
-                }
-                if new_col != col {
-                    grid[row][new_col] = 'O';
-                    grid[row][col] = '.';
+    fn tilt_south(&mut self) {
+        for row in (0..self.rows.saturating_sub(1)).rev() {
+            self.roll_vertically(row, false);
+        }
+    }
+
+    fn tilt_west(&mut self) {
+        self.tilt_horizontally(true);
+    }
+
+    fn tilt_east(&mut self) {
+        self.tilt_horizontally(false);
+    }
+
+    /// Shifts all rocks of each row one column at a time until none can move.
+    fn tilt_horizontally(&mut self, west: bool) {
+        for row in 0..self.rows {
+            loop {
+                let free = self.free(row);
+                let movable = self.rounds[row] & if west { free << 1 } else { free >> 1 };
+                if movable == 0 {
+                    break;
                 }
+                let moved = if west { movable >> 1 } else { movable << 1 };
+                self.rounds[row] = (self.rounds[row] & !movable) | moved;
             }
         }
     }
-    grid
-}
 
-pub fn move_down(mut grid: Vec<Vec<char>>) -> Vec<Vec<char>> {
-    for row in (0..grid.len()).rev() {
-        for col in 0..grid[row].len() {
-            if grid[row][col] == 'O' {
-                let mut new_row = row;
-                while new_row < grid.len() - 1 && grid[new_row + 1][col] == '.' {
-                    new_row += 1;
-                }
-                if new_row != row {
-                    grid[new_row][col] = 'O';
-                    grid[row][col] = '.';
-                }
+    fn spin(&mut self) {
+        for (_, tilt) in SPIN {
+            tilt(self);
+        }
+    }
+
+    fn frame(&self, caption: String) -> Frame {
+        let cols = self.mask.count_ones() as usize;
+        let mut frame = Frame::new(self.rows, cols).with_caption(caption);
+        for row in 0..self.rows {
+            for col in 0..cols {
+                let (glyph, color) = match (self.cubes[row] >> col & 1, self.rounds[row] >> col & 1) {
+                    (1, _) => ('#', Color::Gray),
+                    (_, 1) => ('O', Color::Yellow),
+                    _ => ('.', Color::Default),
+                };
+                frame.set(row, col, glyph, color);
             }
         }
+        frame
     }
-    grid
-}
 
-fn get_grid(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
+    fn load(&self) -> usize {
+        self.rounds
+            .iter()
+            .enumerate()
+            .map(|(row, rounds)| rounds.count_ones() as usize * (self.rows - row))
+            .sum()
+    }
 }
 
-pub fn calculate_checksum(s: &str) -> u64 {
-    let mut hasher = DefaultHasher::new();
-    s.hash(&mut hasher);
-    hasher.finish()
+pub fn part_one(input: &str) -> Option<usize> {
+    let mut platform = Platform::new(input)?;
+    platform.tilt_north();
+    Some(platform.load())
 }
 
-pub fn move_four(mut grid: Vec<Vec<char>>) -> (Vec<Vec<char>>, u64) {
-    grid = move_up(grid);
-    grid = move_left(grid);
-    grid = move_down(grid);
-    grid = move_right(grid);
-    let grid_string: String = grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<String>>().join("\n");
-    let checksum = calculate_checksum(&grid_string);
-    (grid, checksum)
+pub fn part_two(input: &str) -> Option<usize> {
+    let mut platform = Platform::new(input)?;
+    let mut seen: HashMap<Vec<u128>, usize> = HashMap::new();
+    let mut history = Vec::new();
+    for step in 0..CYCLES {
+        if let Some(&start) = seen.get(&platform.rounds) {
+            let state = &history[start + (CYCLES - step) % (step - start)];
+            platform.rounds.clone_from(state);
+            break;
+        }
+        seen.insert(platform.rounds.clone(), step);
+        history.push(platform.rounds.clone());
+        platform.spin();
+    }
+    Some(platform.load())
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    return count_lines_to_bottom(&move_up(get_grid(input))).try_into().ok();
-}
+/// One frame per tilt over the first spin cycles.
+struct Tilting;
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut grid = get_grid(input);
-    let mut checksums = vec![];
-    let mut increment = 0;
-    let max_increment = 1000000000;
-    while increment < max_increment {
-        increment += 1;
-        let check;
-        (grid, check) = move_four(grid);
-        if checksums.contains(&check) {
-            let ecart = checksums.len() - checksums.iter().position(|&x| x == check).unwrap();
-            increment += (max_increment - increment) / ecart * ecart;
-            while increment < max_increment {
-                increment += 1;
-                (grid, _) = move_four(grid);
+impl Visualize for Tilting {
+    fn frames(input: &str) -> Result<Vec<Frame>, String> {
+        let mut platform = Platform::new(input).ok_or("the platform is wider than 128 columns")?;
+        let mut frames = vec![platform.frame(format!("load {}", platform.load()))];
+        for cycle in 1..=VISUALIZED_CYCLES {
+            for (direction, tilt) in SPIN {
+                tilt(&mut platform);
+                frames.push(platform.frame(format!("cycle {cycle}, tilted {direction}: load {}", platform.load())));
             }
-        } else {
-            checksums.push(check);
         }
+        Ok(frames)
     }
-    return count_lines_to_bottom(&grid).try_into().ok();
 }
 
 #[cfg(test)]
@@ -154,4 +190,12 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(64));
     }
+
+    #[test]
+    fn test_visualize() {
+        let frames = Tilting::frames(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(frames.len(), 1 + 4 * VISUALIZED_CYCLES);
+        assert_eq!(frames[1].caption, "cycle 1, tilted north: load 136");
+        assert_eq!(frames[1].get(0, 0).map(|cell| cell.glyph), Some('O'));
+    }
 }
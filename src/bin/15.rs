@@ -1,12 +1,19 @@
 advent_of_code::solution!(15);
 
 use std::collections::HashMap;
+use advent_of_code::ParseError;
 
 pub struct Boxes {
     keys: Vec<String>,
     map: HashMap<String, u32>,
 }
 
+impl Default for Boxes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Boxes {
     pub fn new() -> Self {
         Self {
@@ -54,15 +61,16 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(current_value)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
     let mut current_value: u32 = 0;
     let mut boxes = Boxes::new();
     for line in input.split(",") {
         if line.ends_with('-') {
             boxes.remove(&line[0..line.len() - 1].to_string());
         } else {
-            let (key, value) = line.split_at(line.find('=').unwrap());
-            boxes.insert(key.to_string(), value[1..].parse::<u32>().unwrap());
+            // the whole sequence sits on the first line.
+            let (key, value) = line.split_once('=').ok_or_else(|| ParseError::unexpected_in_line(1, input, line))?;
+            boxes.insert(key.to_string(), ParseError::parse(1, input, value)?);
         }
     }
     let mut slots: HashMap<u32, u32> = HashMap::with_capacity(256);
@@ -73,7 +81,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         *slot += 1;
         current_value += (box_number + 1) * *slot * val;
     }
-    Some(current_value)
+    Ok(current_value)
 }
 
 #[cfg(test)]
@@ -89,6 +97,11 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(145));
+        assert_eq!(result, Ok(145));
+    }
+
+    #[test]
+    fn test_invalid_step() {
+        assert_eq!(part_two("rn=1,cm-,qp"), Err(ParseError::unexpected(1, 10, "qp")));
     }
 }
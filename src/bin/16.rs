@@ -1,289 +1,331 @@
-advent_of_code::solution!(16);
+advent_of_code::solution!(16, visualize = Beams);
 
-const VERTICAL: u32 = 1;
-const HORIZONTAL: u32 = 2;
-const BACKSLASH: u32 = 4;
-const SLASH: u32 = 8;
-const EMPTY: u32 = 0;
-const L_VISITED: u32 = 16;
-const T_VISITED: u32 = 32;
-const R_VISITED: u32 = 64;
-const B_VISITED: u32 = 128;
+use advent_of_code::ParseError;
+use advent_of_code::template::visualize::{Color, Frame, Visualize};
 
-const TL_VISITED: u32 = 48;
-const TR_VISITED: u32 = 96;
-const LB_VISITED: u32 = 144;
-const RB_VISITED: u32 = 192;
-const TB_VISITED: u32 = T_VISITED | B_VISITED;
-const LR_VISITED: u32 = L_VISITED | R_VISITED;
-const ALL_VISITED: u32 = TB_VISITED | LR_VISITED;
+const UP: usize = 0;
+const RIGHT: usize = 1;
+const DOWN: usize = 2;
+const LEFT: usize = 3;
+
+const NO_NODE: usize = usize::MAX;
+const MAX_FRAMES: usize = 300;
 
 pub struct Maze {
-    data: Vec<Vec<u32>>,
-    xmax: usize,
-    ymax: usize,
+    data: Vec<u8>,
+    rows: usize,
+    cols: usize,
 }
 
-
 impl Maze {
-    pub fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
         let mut data = Vec::new();
-        for line in input.lines() {
-            let mut row = Vec::new();
-            for char in line.chars() {
+        let mut rows = 0;
+        let mut cols = 0;
+        for (row, line) in input.lines().enumerate() {
+            for (col, char) in line.chars().enumerate() {
                 match char {
-                    '|' => row.push(VERTICAL),
-                    '-' => row.push(HORIZONTAL),
-                    '\\' => row.push(BACKSLASH),
-                    '/' => row.push(SLASH),
-                    '.' => row.push(EMPTY),
-                    _ => panic!("Invalid char"),
+                    '|' | '-' | '\\' | '/' | '.' => data.push(char as u8),
+                    _ => return Err(ParseError::unexpected(row + 1, col + 1, char)),
                 }
             }
-            data.push(row);
+            if row == 0 {
+                cols = line.len();
+            } else if line.len() != cols {
+                return Err(ParseError::new(row + 1, 1, line, format!("expected {cols} tiles, found {}", line.len())));
+            }
+            rows += 1;
+        }
+        if data.is_empty() {
+            return Err(ParseError::new(1, 1, "", "empty grid"));
         }
-        let xmax = data.len() - 1;
-        let ymax = data[0].len() - 1;
+        Ok(Self { data, rows, cols })
+    }
 
-        Self {
-            data,
-            xmax,
-            ymax,
+    fn step(&self, cell: usize, direction: usize) -> Option<usize> {
+        let (x, y) = (cell / self.cols, cell % self.cols);
+        match direction {
+            UP if x > 0 => Some(cell - self.cols),
+            DOWN if x + 1 < self.rows => Some(cell + self.cols),
+            LEFT if y > 0 => Some(cell - 1),
+            RIGHT if y + 1 < self.cols => Some(cell + 1),
+            _ => None,
         }
     }
 
-    pub fn get_direction(&self, x: usize, y: usize) -> u32 {
-        self.data[x][y] & 15
+    /// Whether a beam arriving in `direction` hits the flat side of a splitter.
+    fn splits(&self, cell: usize, direction: usize) -> bool {
+        matches!((self.data[cell], direction), (b'|', LEFT | RIGHT) | (b'-', UP | DOWN))
     }
 
-    pub fn get_visited(&self, x: usize, y: usize) -> u32 {
-        self.data[x][y] & 240
+    fn bounce(&self, cell: usize, direction: usize) -> usize {
+        match (self.data[cell], direction) {
+            (b'/', UP) | (b'\\', DOWN) => RIGHT,
+            (b'/', RIGHT) | (b'\\', LEFT) => UP,
+            (b'/', DOWN) | (b'\\', UP) => LEFT,
+            (b'/', LEFT) | (b'\\', RIGHT) => DOWN,
+            _ => direction,
+        }
     }
+}
 
-    pub fn print_maze(&self) {
-        for row in &self.data {
-            for cell in row {
-                if cell & VERTICAL == VERTICAL {
-                    print!("|");
-                } else if cell & HORIZONTAL == HORIZONTAL {
-                    print!("-");
-                } else if cell & BACKSLASH == BACKSLASH {
-                    print!("\\");
-                } else if cell & SLASH == SLASH {
-                    print!("/");
-                } else if cell == &0_u32 {
-                    print!(".");
-                } else {
-                    let cell2 = cell & 240;
-                    match cell2 {
-                        T_VISITED => print!("V"),
-                        B_VISITED => print!("^"),
-                        R_VISITED => print!("<"),
-                        L_VISITED => print!(">"),
-                        TR_VISITED => print!("L"),
-                        LB_VISITED => print!("7"),
-                        TL_VISITED => print!("J"),
-                        RB_VISITED => print!("F"),
-                        TB_VISITED => print!("l"),
-                        LR_VISITED => print!("="),
-                        ALL_VISITED => print!("+"),
+/// Tiles crossed by a beam segment, and the splitter it ends on if any.
+struct Segment {
+    tiles: Vec<usize>,
+    splitter: Option<usize>,
+}
 
-                        _ => print!("2"),
-                    }
-                }
-            }
-            print!("\n");
+/// Beam segments between splitters, condensed into strongly connected components so that
+/// the tiles energised from any splitter are computed once and reused for every entry point.
+pub struct BeamGraph<'a> {
+    maze: &'a Maze,
+    /// Node id of each splitter cell, `NO_NODE` for other cells.
+    node_of: Vec<usize>,
+    /// Component of each node.
+    component: Vec<usize>,
+    /// Tiles energised from each component, one bit per cell.
+    energised: Vec<Vec<u64>>,
+    /// Stamps of the `(cell, direction)` states seen by each trace, to stop on mirror loops.
+    seen: Vec<u32>,
+    stamp: u32,
+}
+
+impl<'a> BeamGraph<'a> {
+    pub fn new(maze: &'a Maze) -> Self {
+        let cells = maze.data.len();
+        let mut graph = Self {
+            maze,
+            node_of: vec![NO_NODE; cells],
+            component: Vec::new(),
+            energised: Vec::new(),
+            seen: vec![0; cells * 4],
+            stamp: 0,
+        };
+        let splitters: Vec<usize> = (0..cells).filter(|&cell| matches!(maze.data[cell], b'|' | b'-')).collect();
+        for (node, &cell) in splitters.iter().enumerate() {
+            graph.node_of[cell] = node;
         }
-    }
 
-    pub fn count_visited(&self) -> u32 {
-        let mut count = 0;
-        for row in &self.data {
-            for cell in row {
-                if cell & 240 > 0 {
-                    count += 1;
+        let mut tiles = Vec::with_capacity(splitters.len());
+        let mut successors = Vec::with_capacity(splitters.len());
+        for &cell in &splitters {
+            let mut own = vec![cell];
+            let mut next = Vec::new();
+            let directions = if maze.data[cell] == b'|' { [UP, DOWN] } else { [LEFT, RIGHT] };
+            for direction in directions {
+                if let Some(start) = maze.step(cell, direction) {
+                    let segment = graph.trace(start, direction);
+                    own.extend(segment.tiles);
+                    next.extend(segment.splitter.map(|splitter| graph.node_of[splitter]));
                 }
             }
+            tiles.push(own);
+            successors.push(next);
         }
-        count
-    }
 
-    fn move_up(&mut self, x_orig: usize, y: usize) {
-        if x_orig == 0 {
-            return;
-        }
-        let x = x_orig - 1;
-        if self.data[x][y] & B_VISITED == B_VISITED {
-            return;
+        let (component, members) = tarjan(&successors);
+        let words = cells.div_ceil(64);
+        // Tarjan yields components in reverse topological order, successors come first.
+        let mut energised: Vec<Vec<u64>> = Vec::with_capacity(members.len());
+        for (index, nodes) in members.iter().enumerate() {
+            let mut bits = vec![0; words];
+            for &node in nodes {
+                for &tile in &tiles[node] {
+                    bits[tile / 64] |= 1 << (tile % 64);
+                }
+                for &successor in &successors[node] {
+                    if component[successor] != index {
+                        bits.iter_mut().zip(&energised[component[successor]]).for_each(|(a, b)| *a |= b);
+                    }
+                }
+            }
+            energised.push(bits);
         }
-        //println!("move up x: {}, y: {}", x, y);
-
-        self.data[x_orig][y] |= T_VISITED;
-
-        self.move_to_up(x, y);
+        graph.component = component;
+        graph.energised = energised;
+        graph
     }
 
-    fn move_to_up(&mut self, x: usize, y: usize) {
-        self.data[x][y] |= B_VISITED;
-
-        match self.get_direction(x, y) {
-            HORIZONTAL => {
-                self.move_left(x, y);
-                self.move_right(x, y);
+    fn trace(&mut self, mut cell: usize, mut direction: usize) -> Segment {
+        self.stamp += 1;
+        let mut tiles = Vec::new();
+        loop {
+            let state = cell * 4 + direction;
+            if self.seen[state] == self.stamp {
+                return Segment { tiles, splitter: None };
             }
-            VERTICAL | EMPTY => {
-                self.move_up(x, y);
+            self.seen[state] = self.stamp;
+            tiles.push(cell);
+            if self.maze.splits(cell, direction) {
+                return Segment { tiles, splitter: Some(cell) };
             }
-            BACKSLASH => {
-                self.move_left(x, y);
+            direction = self.maze.bounce(cell, direction);
+            match self.maze.step(cell, direction) {
+                Some(next) => cell = next,
+                None => return Segment { tiles, splitter: None },
             }
-            SLASH => {
-                self.move_right(x, y);
-            }
-            _ => (),
         }
     }
 
-    fn move_down(&mut self, x_orig: usize, y: usize) {
-        if x_orig == self.xmax {
-            return;
-        }
-        let x = x_orig + 1;
-        if self.data[x][y] & T_VISITED == T_VISITED {
-            return;
+    /// Number of tiles energised by a beam entering `cell` heading in `direction`.
+    pub fn count_energised(&mut self, cell: usize, direction: usize) -> u32 {
+        let segment = self.trace(cell, direction);
+        let mut bits = match segment.splitter {
+            Some(splitter) => self.energised[self.component[self.node_of[splitter]]].clone(),
+            None => vec![0; self.maze.data.len().div_ceil(64)],
+        };
+        for tile in segment.tiles {
+            bits[tile / 64] |= 1 << (tile % 64);
         }
-        //println!("move down x: {}, y: {}", x, y);
-        self.data[x_orig][y] |= B_VISITED;
-
-        self.move_to_down(x, y);
+        bits.iter().map(|word| word.count_ones()).sum()
     }
+}
 
-    fn move_to_down(&mut self, x: usize, y: usize) {
-        self.data[x][y] |= T_VISITED;
+/// Tarjan's strongly connected components. Returns the component of each node and the
+/// nodes of each component, components listed in reverse topological order.
+fn tarjan(successors: &[Vec<usize>]) -> (Vec<usize>, Vec<Vec<usize>>) {
+    struct State<'s> {
+        successors: &'s [Vec<usize>],
+        index: Vec<usize>,
+        low: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next_index: usize,
+        component: Vec<usize>,
+        members: Vec<Vec<usize>>,
+    }
 
-        match self.get_direction(x, y) {
-            HORIZONTAL => {
-                self.move_left(x, y);
-                self.move_right(x, y);
-            }
-            VERTICAL | EMPTY => {
-                self.move_down(x, y);
-            }
-            BACKSLASH => {
-                self.move_right(x, y);
+    fn visit(state: &mut State, node: usize) {
+        state.index[node] = state.next_index;
+        state.low[node] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(node);
+        state.on_stack[node] = true;
+        for &next in &state.successors[node] {
+            if state.index[next] == NO_NODE {
+                visit(state, next);
+                state.low[node] = state.low[node].min(state.low[next]);
+            } else if state.on_stack[next] {
+                state.low[node] = state.low[node].min(state.index[next]);
             }
-            SLASH => {
-                self.move_left(x, y);
+        }
+        if state.low[node] == state.index[node] {
+            let mut nodes = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack[member] = false;
+                state.component[member] = state.members.len();
+                nodes.push(member);
+                if member == node {
+                    break;
+                }
             }
-            _ => (),
+            state.members.push(nodes);
         }
     }
 
-    fn move_left(&mut self, x: usize, y_orig: usize) {
-        if y_orig == 0 {
-            return;
+    let count = successors.len();
+    let mut state = State {
+        successors,
+        index: vec![NO_NODE; count],
+        low: vec![0; count],
+        on_stack: vec![false; count],
+        stack: Vec::new(),
+        next_index: 0,
+        component: vec![0; count],
+        members: Vec::new(),
+    };
+    for node in 0..count {
+        if state.index[node] == NO_NODE {
+            visit(&mut state, node);
         }
-        let y = y_orig - 1;
-        if self.data[x][y] & R_VISITED == R_VISITED {
-            return;
-        }
-
-        self.data[x][y_orig] |= L_VISITED;
-
-        self.move_to_left(x, y);
     }
+    (state.component, state.members)
+}
 
-    fn move_to_left(&mut self, x: usize, y: usize) {
-        self.data[x][y] |= R_VISITED;
-        //println!("move left x: {}, y: {}", x, y);
-
-        match self.get_direction(x, y) {
-            HORIZONTAL | EMPTY => {
-                self.move_left(x, y);
-            }
-            VERTICAL => {
-                self.move_up(x, y);
-                self.move_down(x, y);
-            }
-            BACKSLASH => {
-                self.move_up(x, y);
-            }
-            SLASH => {
-                self.move_down(x, y);
+/// The beam of part one spreading from the top-left corner, one tile per tick.
+struct Beams;
+
+impl Beams {
+    /// Beam front at each tick, and the tick at which each tile got energised (`usize::MAX`
+    /// for tiles the beam never reaches).
+    fn ticks(maze: &Maze) -> (Vec<Vec<usize>>, Vec<usize>) {
+        let mut seen = vec![false; maze.data.len() * 4];
+        let mut energised_at = vec![usize::MAX; maze.data.len()];
+        let mut fronts = Vec::new();
+        let mut front = vec![(0, RIGHT)];
+        while !front.is_empty() {
+            let mut next = Vec::new();
+            for &(cell, direction) in &front {
+                energised_at[cell] = energised_at[cell].min(fronts.len());
+                let directions = match (maze.splits(cell, direction), maze.data[cell]) {
+                    (true, b'|') => vec![UP, DOWN],
+                    (true, _) => vec![LEFT, RIGHT],
+                    (false, _) => vec![maze.bounce(cell, direction)],
+                };
+                for direction in directions {
+                    if let Some(cell) = maze.step(cell, direction).filter(|&cell| !seen[cell * 4 + direction]) {
+                        seen[cell * 4 + direction] = true;
+                        next.push((cell, direction));
+                    }
+                }
             }
-            _ => (),
-        }
-    }
-
-    fn move_right(&mut self, x: usize, y_orig: usize) {
-        if y_orig == self.ymax {
-            return;
+            fronts.push(front.iter().map(|&(cell, _)| cell).collect());
+            front = next;
         }
-        let y = y_orig + 1;
-        if self.data[x][y] & L_VISITED == L_VISITED {
-            return;
-        }
-        self.data[x][y_orig] |= R_VISITED;
-        self.move_to_right(x, y);
+        (fronts, energised_at)
     }
+}
 
-    fn move_to_right(&mut self, x: usize, y: usize) {
-        self.data[x][y] |= L_VISITED;
-
-        match self.get_direction(x, y) {
-            HORIZONTAL | EMPTY => {
-                self.move_right(x, y);
-            }
-            VERTICAL => {
-                self.move_up(x, y);
-                self.move_down(x, y);
+impl Visualize for Beams {
+    fn frames(input: &str) -> Result<Vec<Frame>, String> {
+        let maze = Maze::new(input).map_err(|e| e.to_string())?;
+        let (fronts, energised_at) = Self::ticks(&maze);
+        let stride = fronts.len().div_ceil(MAX_FRAMES);
+        let mut frames = Vec::new();
+        for (tick, front) in fronts.iter().enumerate() {
+            if tick % stride != 0 && tick + 1 != fronts.len() {
+                continue;
             }
-            BACKSLASH => {
-                self.move_down(x, y);
+            let mut frame = Frame::new(maze.rows, maze.cols);
+            for (cell, (&tile, &at)) in maze.data.iter().zip(&energised_at).enumerate() {
+                let (glyph, color) = match (tile, at <= tick) {
+                    (b'.', true) => ('#', Color::Yellow),
+                    (b'.', false) => ('.', Color::Default),
+                    (tile, true) => (tile as char, Color::Cyan),
+                    (tile, false) => (tile as char, Color::Gray),
+                };
+                frame.set(cell / maze.cols, cell % maze.cols, glyph, color);
             }
-            SLASH => {
-                self.move_up(x, y);
-            }
-            _ => (),
-        }
-    }
-
-    pub fn clear_visited(&mut self) {
-        for i in 0..self.xmax +1 {
-            for j in 0..self.ymax +1 {
-                self.data[i][j] &= 15;
+            for &cell in front {
+                frame.set(cell / maze.cols, cell % maze.cols, '*', Color::Red);
             }
+            let count = energised_at.iter().filter(|&&at| at <= tick).count();
+            frames.push(frame.with_caption(format!("tick {}: {count} tiles energised", tick + 1)));
         }
+        Ok(frames)
     }
 }
 
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut maze = Maze::new(input);
-    maze.move_to_right(0, 0);
-    return Some(maze.count_visited());
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let maze = Maze::new(input)?;
+    let mut graph = BeamGraph::new(&maze);
+    Ok(graph.count_energised(0, RIGHT))
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut maze = Maze::new(input);
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    let maze = Maze::new(input)?;
+    let mut graph = BeamGraph::new(&maze);
+    let (rows, cols) = (maze.rows, maze.cols);
     let mut max = 0;
-    for i in 0..maze.xmax+1 {
-        maze.clear_visited();
-        maze.move_to_right(i, 0);
-        max = max.max(maze.count_visited());
-        maze.clear_visited();
-        maze.move_to_left(i, maze.ymax);
-        max = max.max(maze.count_visited());
+    for i in 0..rows {
+        max = max.max(graph.count_energised(i * cols, RIGHT));
+        max = max.max(graph.count_energised(i * cols + cols - 1, LEFT));
     }
-    for j in 0..maze.ymax+1 {
-        maze.clear_visited();
-        maze.move_to_down(0, j);
-        max = max.max(maze.count_visited());
-        maze.clear_visited();
-        maze.move_to_up(maze.xmax, j);
-        max = max.max(maze.count_visited());
+    for j in 0..cols {
+        max = max.max(graph.count_energised(j, DOWN));
+        max = max.max(graph.count_energised((rows - 1) * cols + j, UP));
     }
-    return Some(max);
+    Ok(max)
 }
 
 #[cfg(test)]
@@ -293,12 +335,24 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(46));
+        assert_eq!(result, Ok(46));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(51));
+        assert_eq!(result, Ok(51));
+    }
+
+    #[test]
+    fn test_visualize() {
+        let frames = Beams::frames(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(frames.last().map(|frame| frame.caption.ends_with(": 46 tiles energised")), Some(true));
+    }
+
+    #[test]
+    fn test_invalid_char() {
+        let result = part_one(".|.\n.@.\n...");
+        assert_eq!(result, Err(ParseError::unexpected(2, 2, '@')));
     }
 }
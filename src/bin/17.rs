@@ -1,61 +1,105 @@
-advent_of_code::solution!(17);
+advent_of_code::solution!(17, visualize = Crucibles, parse = parse);
 
 
 use std::collections::BinaryHeap;
 use std::cmp::Ordering;
+use advent_of_code::ParseError;
+use advent_of_code::template::visualize::{Color, Frame, Visualize};
 
 const UP: usize = 0;
 const DOWN: usize = 1;
 const LEFT: usize = 2;
 const RIGHT: usize = 3;
 
+const NO_STATE: usize = usize::MAX;
+const FRAMES_PER_ROUTE: usize = 100;
+
+/// Heat loss accumulator. Sums are checked so that large grids report an error
+/// instead of silently wrapping.
+pub trait Cost: Copy + Ord + Default {
+    fn from_digit(digit: u8) -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(impl Cost for $t {
+            fn from_digit(digit: u8) -> Self {
+                digit.into()
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+        })*
+    };
+}
+
+impl_cost!(u8, u16, u32, u64, usize);
+
 #[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    cost: u16,
+struct State<C> {
+    cost: C,
     position: usize,
     direction: usize,
+    /// Index in the distance table of the state this one was pushed from.
+    from: usize,
 }
 
-impl Ord for State {
+impl<C: Cost> Ord for State<C> {
     fn cmp(&self, other: &Self) -> Ordering {
         other.cost.cmp(&self.cost)
     }
 }
 
-impl PartialOrd for State {
+impl<C: Cost> PartialOrd for State<C> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-struct Maze {
-    data: Vec<u16>,
+/// Cheapest route found by `find_path`: its total heat loss and every block it enters.
+#[derive(Debug, PartialEq)]
+pub struct Route<C> {
+    pub cost: C,
+    pub positions: Vec<usize>,
+}
+
+pub struct Maze<C> {
+    data: Vec<C>,
     xlen: usize,
     ylen: usize,
 }
 
-impl Maze {
-    pub fn new(input: &str) -> Self {
+impl<C: Cost> Maze<C> {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
         let mut data = Vec::new();
         let mut ylen: usize = 0;
         let mut xlen: usize = 0;
-        for line in input.lines() {
-            for char in line.chars() {
-                data.push(char.to_string().parse::<u16>().unwrap_or(u16::MAX));
+        for (row, line) in input.lines().enumerate() {
+            for (col, char) in line.chars().enumerate() {
+                let digit = char.to_digit(10).ok_or_else(|| ParseError::unexpected(row + 1, col + 1, char))?;
+                data.push(C::from_digit(digit as u8));
             }
-            xlen += 1;
             if ylen == 0 {
                 ylen = line.len();
+            } else if line.len() != ylen {
+                return Err(ParseError::new(row + 1, 1, line, format!("expected {ylen} blocks, found {}", line.len())));
             }
+            xlen += 1;
+        }
+        if data.is_empty() {
+            return Err(ParseError::new(1, 1, "", "empty grid"));
         }
 
-        Self {
+        Ok(Self {
             data,
             xlen,
             ylen,
-        }
+        })
     }
 
+    /// The block next to `position` in `direction`, `None` at the edge of the grid or for an unknown direction.
     pub fn deplace(&self, direction: usize, position: usize) -> Option<usize> {
         let (x, y) = (position / self.ylen, position % self.ylen);
         match direction {
@@ -63,33 +107,53 @@ impl Maze {
             DOWN => if x < self.xlen - 1 { Some(position + self.ylen) } else { None },
             LEFT => if y > 0 { Some(position - 1) } else { None },
             RIGHT => if y < self.ylen - 1 { Some(position + 1) } else { None },
-            _ => panic!("Invalid direction"),
+            _ => None,
+        }
+    }
+
+    /// Draws the grid with the blocks of `route` replaced by the direction they are entered in.
+    pub fn render_route(&self, route: &Route<C>) -> String {
+        let mut cells: Vec<char> = vec!['.'; self.data.len()];
+        let mut previous = 0;
+        for &position in &route.positions {
+            cells[position] = match position as isize - previous as isize {
+                1 => '>',
+                -1 => '<',
+                d if d > 0 => 'v',
+                _ => '^',
+            };
+            previous = position;
         }
+        cells.chunks(self.ylen).map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
     }
 }
 
-fn get_new_direction(direction: usize) -> [usize; 2] {
+fn get_new_direction(direction: usize) -> Option<[usize; 2]> {
     match direction {
-        UP | DOWN => [LEFT, RIGHT],
-        LEFT | RIGHT => [UP, DOWN],
-        _ => panic!("Invalid direction"),
+        UP | DOWN => Some([LEFT, RIGHT]),
+        LEFT | RIGHT => Some([UP, DOWN]),
+        _ => None,
     }
 }
 
-pub fn find_path(input: &str, pas_min: usize, pas_max: usize) -> Option<u16> {
-    let grid = Maze::new(input);
+/// Cheapest route from the top-left to the bottom-right block, moving at least `pas_min`
+/// and at most `pas_max` blocks before each turn. Returns `Ok(None)` if the end can't be
+/// reached and an error if the heat loss overflows `C` or `pas_max` is not above `pas_min`.
+pub fn find_path<C: Cost>(grid: &Maze<C>, pas_min: usize, pas_max: usize) -> Result<Option<Route<C>>, String> {
+    if pas_max <= pas_min {
+        return Err(format!("the maximum run of {pas_max} blocks should exceed the minimum of {pas_min}"));
+    }
     let ecart = pas_max - pas_min;
+    let cells = grid.data.len();
+    // one entry per (direction, extra steps beyond `pas_min`, position).
+    let key = |direction: usize, i: usize, position: usize| (direction * ecart + i) * cells + position;
 
-    let mut dist = [
-        vec![vec![u16::MAX; grid.data.len() * 3 * 4]; ecart],
-        vec![vec![u16::MAX; grid.data.len() * 3 * 4]; ecart],
-        vec![vec![u16::MAX; grid.data.len() * 3 * 4]; ecart],
-        vec![vec![u16::MAX; grid.data.len() * 3 * 4]; ecart]
-    ];
+    let mut dist: Vec<Option<C>> = vec![None; 4 * ecart * cells];
+    let mut prev: Vec<usize> = vec![NO_STATE; 4 * ecart * cells];
     let mut heap = BinaryHeap::new();
 
-    heap.push(State { cost: 0, position: 0, direction: RIGHT });
-    heap.push(State { cost: 0, position: 0, direction: DOWN });
+    heap.push(State { cost: C::default(), position: 0, direction: RIGHT, from: NO_STATE });
+    heap.push(State { cost: C::default(), position: 0, direction: DOWN, from: NO_STATE });
 
     'prochain: while let Some(state) = heap.pop() {
         let mut next_position = state.position;
@@ -99,38 +163,98 @@ pub fn find_path(input: &str, pas_min: usize, pas_max: usize) -> Option<u16> {
                 Some(pos) => pos,
                 None => continue 'prochain,
             };
-            next_cost = next_cost + grid.data[next_position];
+            next_cost = next_cost.checked_add(grid.data[next_position]).ok_or("heat loss overflows the cost type")?;
         }
-        for i in 1..(1 + ecart) {
+        for i in 0..ecart {
             next_position = match grid.deplace(state.direction, next_position) {
                 Some(pos) => pos,
                 None => continue 'prochain,
             };
-            next_cost = next_cost + grid.data[next_position];
-            if next_cost < dist[state.direction][i - 1][next_position] {
-                for new_direction in get_new_direction(state.direction) {
-                    heap.push(State { cost: next_cost, position: next_position, direction: new_direction });
+            next_cost = next_cost.checked_add(grid.data[next_position]).ok_or("heat loss overflows the cost type")?;
+            let k = key(state.direction, i, next_position);
+            if dist[k].is_none_or(|d| next_cost < d) {
+                for new_direction in get_new_direction(state.direction).into_iter().flatten() {
+                    heap.push(State { cost: next_cost, position: next_position, direction: new_direction, from: k });
                 }
-                dist[state.direction][i - 1][next_position] = next_cost;
+                dist[k] = Some(next_cost);
+                prev[k] = state.from;
+            }
+        }
+    }
+
+    let end = cells - 1;
+    let best = (0..4)
+        .flat_map(|direction| (0..ecart).map(move |i| key(direction, i, end)))
+        .filter_map(|k| dist[k].map(|cost| (cost, k)))
+        .min();
+    let Some((cost, mut k)) = best else {
+        return Ok(None);
+    };
+
+    let mut positions = Vec::new();
+    while k != NO_STATE {
+        let (direction, position) = (k / cells / ecart, k % cells);
+        let start = if prev[k] == NO_STATE { 0 } else { prev[k] % cells };
+        let mut segment = vec![position];
+        let mut current = position;
+        let back = match direction { UP => DOWN, DOWN => UP, LEFT => RIGHT, _ => LEFT };
+        while let Some(before) = grid.deplace(back, current).filter(|_| current != start) {
+            if before != start {
+                segment.push(before);
             }
+            current = before;
         }
+        positions.extend(segment);
+        k = prev[k];
     }
-    let j = grid.data.len() - 1;
-    let mut minval = u16::MAX;
-    for i in 0..ecart {
-        for k in 0..4 {
-            minval = minval.min(dist[k][i][j]);
+    positions.reverse();
+    Ok(Some(Route { cost, positions }))
+}
+
+/// The cheapest routes of both parts, drawn block by block over the heat loss map.
+struct Crucibles;
+
+impl Visualize for Crucibles {
+    fn frames(input: &str) -> Result<Vec<Frame>, String> {
+        let grid = Maze::<u32>::new(input).map_err(|e| e.to_string())?;
+        let digits: String = input.lines().collect::<Vec<_>>().join("\n");
+        let mut frames = Vec::new();
+        for (name, pas_min, pas_max) in [("crucible", 0, 3), ("ultra crucible", 3, 10)] {
+            let Some(route) = find_path(&grid, pas_min, pas_max)? else {
+                continue;
+            };
+            let arrows: Vec<char> = grid.render_route(&route).chars().filter(|&c| c != '\n').collect();
+            let stride = route.positions.len().div_ceil(FRAMES_PER_ROUTE);
+            for end in (1..=route.positions.len()).filter(|&end| end % stride == 0 || end == route.positions.len()) {
+                let mut frame = Frame::from_text(&digits, |_| Color::Gray);
+                let mut heat_loss = 0;
+                for &position in &route.positions[..end] {
+                    heat_loss += grid.data[position];
+                    frame.set(position / grid.ylen, position % grid.ylen, arrows[position], Color::Red);
+                }
+                frames.push(frame.with_caption(format!("{name}: heat loss {heat_loss} of {}", route.cost)));
+            }
         }
+        Ok(frames)
     }
-    Some(minval)
 }
 
-pub fn part_one(input: &str) -> Option<u16> {
-    find_path(input, 0, 3)
+/// The heat loss map, parsed once for both parts.
+pub fn parse(input: &str) -> Result<Maze<u32>, ParseError> {
+    Maze::new(input)
 }
 
-pub fn part_two(input: &str) -> Option<u16> {
-    find_path(input, 3, 10)
+fn least_heat_loss(grid: &Maze<u32>, pas_min: usize, pas_max: usize) -> Result<u32, String> {
+    let route = find_path(grid, pas_min, pas_max)?.ok_or("no route reaches the bottom-right block")?;
+    Ok(route.cost)
+}
+
+pub fn part_one(grid: &Maze<u32>) -> Result<u32, String> {
+    least_heat_loss(grid, 0, 3)
+}
+
+pub fn part_two(grid: &Maze<u32>) -> Result<u32, String> {
+    least_heat_loss(grid, 3, 10)
 }
 
 #[cfg(test)]
@@ -140,7 +264,7 @@ mod tests {
     #[test]
     fn test_maze() {
         let input = advent_of_code::template::read_file("examples", DAY);
-        let grid = Maze::new(&input);
+        let grid = Maze::<u16>::new(&input).unwrap();
 
         let mut pos = 0_usize;
         assert_eq!(grid.data[pos], 2);
@@ -153,18 +277,57 @@ mod tests {
         pos = grid.deplace(LEFT, pos).unwrap();
         assert_eq!(grid.data[pos], 2);
         assert_eq!(pos, 0);
+        assert_eq!(grid.deplace(4, pos), None);
+        assert_eq!(get_new_direction(4), None);
     }
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(102));
+        let grid = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(part_one(&grid), Ok(102));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(94));
+        let grid = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(part_two(&grid), Ok(94));
     }
 
+    #[test]
+    fn test_route() {
+        let grid = Maze::<u64>::new(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        for (pas_min, pas_max) in [(0, 3), (3, 10)] {
+            let route = find_path(&grid, pas_min, pas_max).unwrap().unwrap();
+            let total: u64 = route.positions.iter().map(|&p| grid.data[p]).sum();
+            assert_eq!(total, route.cost);
+            assert_eq!(route.positions.last(), Some(&(grid.data.len() - 1)));
+        }
+    }
+
+    #[test]
+    fn test_visualize() {
+        let frames = Crucibles::frames(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert!(frames.iter().any(|frame| frame.caption == "crucible: heat loss 102 of 102"));
+        assert_eq!(frames.last().map(|frame| frame.caption.as_str()), Some("ultra crucible: heat loss 94 of 94"));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = vec!["9".repeat(20); 20].join("\n");
+        let grid = Maze::<u8>::new(&input).unwrap();
+        assert!(find_path(&grid, 0, 3).is_err());
+    }
+
+    #[test]
+    fn test_invalid_steps() {
+        let grid = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert!(find_path(&grid, 3, 3).is_err());
+        assert!(find_path(&grid, 10, 3).is_err());
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        assert_eq!(Maze::<u32>::new("12\n3x").err(), Some(ParseError::unexpected(2, 2, 'x')));
+        assert!(Maze::<u32>::new("12\n345").is_err());
+    }
 }
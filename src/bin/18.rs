@@ -1,5 +1,7 @@
 advent_of_code::solution!(18);
 
+use advent_of_code::ParseError;
+
 enum Angle {
     HautGauche,
     HautDroite,
@@ -25,7 +27,8 @@ impl MazeContructor {
         }
     }
 
-    fn add_trou(&mut self, direction: &str, distance: i64) {
+    /// Digs `distance` meters towards `direction`, which must turn from the previous step.
+    fn add_trou(&mut self, direction: &str, distance: i64) -> Result<(), &'static str> {
         match direction {
             "R" => {
                 match self.current_angle {
@@ -42,7 +45,7 @@ impl MazeContructor {
                         self.last_y += distance;
                     }
                     Angle::BasGauche => {
-                        panic!("Invalid direction");
+                        return Err(self.invalid_turn());
                     }
                 }
                 self.current_angle = Angle::HautDroite;
@@ -57,7 +60,7 @@ impl MazeContructor {
                         self.last_y -= distance;
                     }
                     Angle::HautDroite => {
-                        panic!("Invalid direction");
+                        return Err(self.invalid_turn());
                     }
                     Angle::BasDroite => {
                         self.last_y -= distance + 1;
@@ -81,7 +84,7 @@ impl MazeContructor {
                         self.last_x -= distance;
                     }
                     Angle::BasDroite => {
-                        panic!("Invalid direction");
+                        return Err(self.invalid_turn());
                     }
                     Angle::BasGauche => {
                         self.last_x -= distance + 1;
@@ -93,7 +96,7 @@ impl MazeContructor {
             "D" => {
                 match self.current_angle {
                     Angle::HautGauche => {
-                        panic!("Invalid direction");
+                        return Err(self.invalid_turn());
                     }
                     Angle::HautDroite => {
                         self.last_x += distance + 1;
@@ -111,34 +114,65 @@ impl MazeContructor {
                 self.current_angle = Angle::BasDroite;
                 self.sommets.push((self.last_x, self.last_y));
             }
-            _ => panic!("Invalid direction"),
+            _ => return Err("expected U, D, L or R"),
+        }
+        Ok(())
+    }
+
+    fn invalid_turn(&self) -> &'static str {
+        if self.sommets.len() > 1 {
+            "expected a turn, found a step back the way the previous one came"
+        } else {
+            "expected the plan to start right, left or up"
         }
     }
 
-    fn read_input_part1(&mut self, input: &str) {
-        for line in input.lines() {
+    fn read_input_part1(&mut self, input: &str) -> Result<(), ParseError> {
+        for (row, line) in input.lines().enumerate() {
             let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() == 3 {
-                let distance = parts[1].parse::<i64>().unwrap();
-                self.add_trou(parts[0], distance);
+            let [direction, distance, _] = parts[..] else {
+                return Err(ParseError::new(row + 1, 1, line, "expected a direction, a distance and a color"));
+            };
+            if !matches!(direction, "U" | "D" | "L" | "R") {
+                return Err(ParseError::unexpected_in_line(row + 1, line, direction));
             }
+            let distance = ParseError::parse::<i64>(row + 1, line, distance)?;
+            self.add_trou(direction, distance)
+                .map_err(|message| ParseError::in_line(row + 1, line, direction, message))?;
         }
+        Ok(())
     }
 
-    fn read_input_part2(&mut self, input: &str) {
-        for line in input.lines() {
+    fn read_input_part2(&mut self, input: &str) -> Result<(), ParseError> {
+        for (row, line) in input.lines().enumerate() {
             let parts: Vec<&str> = line.split_whitespace().collect();
-            let color_code = &parts[2][2..7]; // Get the 5 characters after #
-            let distance = i64::from_str_radix(color_code, 16).unwrap(); // Convert from base 16 to base 10
-            let direction = parts[2].chars().nth(7).unwrap(); // Get the 6th character
-            match direction {
-                '0' => { self.add_trou("R", distance); }
-                '1' => { self.add_trou("D", distance); }
-                '2' => { self.add_trou("L", distance); }
-                '3' => { self.add_trou("U", distance); }
-                _ => panic!("Invalid direction"),
+            let Some(&color) = parts.get(2) else {
+                return Err(ParseError::end_of_line(row + 1, line, "a color"));
+            };
+            // `(#70c710)`: the first five hex digits are the distance, the last one the direction.
+            let Some(color_code) = color.strip_prefix("(#").and_then(|c| c.strip_suffix(')')) else {
+                return Err(ParseError::unexpected_in_line(row + 1, line, color));
+            };
+            // checked before slicing by bytes below.
+            if let Some((index, c)) = color_code.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+                return Err(ParseError::unexpected_in_line(row + 1, line, &color_code[index..index + c.len_utf8()]));
             }
+            if color_code.len() != 6 {
+                return Err(ParseError::in_line(row + 1, line, color_code, "expected 6 hexadecimal digits"));
+            }
+            let distance = i64::from_str_radix(&color_code[..5], 16)
+                .map_err(|_| ParseError::unexpected_in_line(row + 1, line, &color_code[..5]))?;
+            let direction = match &color_code[5..] {
+                "0" => "R",
+                "1" => "D",
+                "2" => "L",
+                "3" => "U",
+                direction => return Err(ParseError::unexpected_in_line(row + 1, line, direction)),
+            };
+            self.add_trou(direction, distance)
+                .map_err(|message| ParseError::in_line(row + 1, line, &color_code[5..], message))?;
         }
+        Ok(())
     }
 
     fn count(&self) -> f64 {
@@ -154,19 +188,19 @@ impl MazeContructor {
 }
 
 
-pub fn part_one(input: &str) -> Option<u64> {
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
     let mut maze_constructor = MazeContructor::new();
-    maze_constructor.read_input_part1(input);
+    maze_constructor.read_input_part1(input)?;
     let maze = maze_constructor.count();
-    Some(maze as u64)
+    Ok(maze as u64)
 }
 
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
     let mut maze_constructor = MazeContructor::new();
-    maze_constructor.read_input_part2(input);
+    maze_constructor.read_input_part2(input)?;
     let maze = maze_constructor.count();
-    Some(maze as u64)
+    Ok(maze as u64)
 }
 
 #[cfg(test)]
@@ -177,13 +211,34 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(62));
+        assert_eq!(result, Ok(62));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(952408144115));
+        assert_eq!(result, Ok(952408144115));
+    }
+
+    #[test]
+    fn test_invalid_color() {
+        let result = part_two("R 6 (#70c710)\nD 5 (#0dc5é1)");
+        assert_eq!(result.map_err(|e| (e.line, e.column, e.text)), Err((2, 11, "é".to_string())));
+        let result = part_two("R 6 (#70c71)");
+        assert_eq!(result.map_err(|e| (e.column, e.message)), Err((7, "expected 6 hexadecimal digits".to_string())));
+    }
+
+    #[test]
+    fn test_invalid_instruction() {
+        assert_eq!(part_one("R 6 (#70c710)\nX 5 (#0dc571)"), Err(ParseError::unexpected(2, 1, "X")));
+        assert_eq!(part_two("R 6 (#70c710)\nD 5 (#0dc57g)"), Err(ParseError::unexpected(2, 12, "g")));
+    }
+
+    #[test]
+    fn test_reversed_direction() {
+        let message = "expected a turn, found a step back the way the previous one came";
+        assert_eq!(part_one("R 2 (#000000)\nL 2 (#000000)"), Err(ParseError::new(2, 1, "L", message)));
+        assert_eq!(part_two("R 2 (#000020)\nL 2 (#000022)"), Err(ParseError::new(2, 12, "2", message)));
     }
 
 }
\ No newline at end of file
@@ -1,12 +1,14 @@
 advent_of_code::solution!(19);
 
-const X: u8 = 'x' as u8;
-const M: u8 = 'm' as u8;
-const A: u8 = 'a' as u8;
-const S: u8 = 's' as u8;
+use advent_of_code::parse::{self, Line, ParseError};
 
-const GREATER: u8 = '>' as u8;
-const LESSER: u8 = '<' as u8;
+const X: u8 = b'x';
+const M: u8 = b'm';
+const A: u8 = b'a';
+const S: u8 = b's';
+
+const GREATER: u8 = b'>';
+const LESSER: u8 = b'<';
 
 const START_VALUE: (u32,u32) = (1, 4000);
 
@@ -18,28 +20,17 @@ struct Instruction {
 }
 
 impl Instruction {
-    pub fn new(input: &str) -> Self {
-        let parts: Vec<&str> = input.split(':').collect();
-        let action = parts.last().unwrap().to_string();
-        if parts[0].contains('>') || parts[0].contains('<') {
-            let bytes = parts[0].as_bytes();
-            let attribut = match bytes[0] {
-                X => X,
-                M => M,
-                A => A,
-                S => S,
-                _ => panic!("Unknown attribut"),
-            };
-            let condition = match bytes[1] {
-                GREATER => GREATER,
-                LESSER => LESSER,
-                _ => panic!("Unknown condition"),
-            };
-            let value = parts[0][2..].parse().unwrap();
-            Self { attribut, condition, value, action }
-        } else {
-            Self { attribut: 0, condition: 0, value: 0, action }
-        }
+    pub fn new(line: &Line, input: &str) -> Result<Self, ParseError> {
+        let Some((condition, action)) = input.split_once(':') else {
+            return Ok(Self { attribut: 0, condition: 0, value: 0, action: input.to_string() });
+        };
+        let action = action.to_string();
+        let (attribut, condition_byte) = match condition.as_bytes() {
+            [attribut @ (X | M | A | S), condition @ (GREATER | LESSER), ..] => (*attribut, *condition),
+            _ => return Err(line.error(condition, "expected a rating followed by '<' or '>'")),
+        };
+        let value = line.parse(&condition[2..])?;
+        Ok(Self { attribut, condition: condition_byte, value, action })
     }
 
     pub fn correspond(&self, part: &mut Part) -> bool {
@@ -62,7 +53,7 @@ impl Instruction {
         if condition_satisfied {
             part.label = self.action.clone();
         }
-        return condition_satisfied;
+        condition_satisfied
     }
 
     fn check_ranges(&self, range: &Ranges) -> Option<(Ranges, Ranges)> {
@@ -139,9 +130,9 @@ impl Instruction {
             _ => panic!("Unknown field: {}", self.attribut),
         }
         if found {
-            return Some((match_range, left_range));
+            Some((match_range, left_range))
         } else {
-            return None;
+            None
         }
     }
 }
@@ -211,41 +202,55 @@ impl Solver {
         }
     }
 
-    pub fn solve_part1(&mut self, input: &str) -> Option<u32> {
-        self.parse_input(input);
-        Some(self.process_parts())
+    pub fn solve_part1(&mut self, input: &str) -> Result<u32, ParseError> {
+        self.parse_input(input)?;
+        Ok(self.process_parts())
     }
 
-    pub fn solve_part2(&mut self, input: &str) -> Option<u64> {
-        self.parse_input(input);
-        Some(self.find_ranges())
+    pub fn solve_part2(&mut self, input: &str) -> Result<u64, ParseError> {
+        self.parse_input(input)?;
+        Ok(self.find_ranges())
     }
 
-    fn parse_input(&mut self, input: &str) {
-        let mut is_instruction = true;
-
-        for line in input.lines() {
-            if line.trim().is_empty() {
-                is_instruction = false;
-                continue;
-            }
-            if is_instruction {
-                let mut split = line.splitn(2, '{');
-                let label = split.next().unwrap().to_string();
-                let actions = split.next().unwrap().trim_end_matches('}').split(',').map(|s| Instruction::new(s)).collect();
-                self.instructions.push(Workflow { label, actions });
-            } else {
-                let values: Vec<u32> = line.trim_start_matches('{').trim_end_matches('}').split(',').map(|s| s[2..].trim().parse().unwrap()).collect();
-                let part = Part { x: values[0], m: values[1], a: values[2], s: values[3], label: "in".to_string() };
-                self.parts.push(part);
+    fn parse_input(&mut self, input: &str) -> Result<(), ParseError> {
+        let mut blocks = parse::blocks(input).into_iter();
+        let workflows = blocks.next().unwrap_or_default();
+        let parts = blocks.next().unwrap_or_default();
+        if let Some(extra) = blocks.next() {
+            return Err(extra[0].error(extra[0].text, "expected workflows then parts, found a third block"));
+        }
+        let mut targets = vec![];
+        for line in &workflows {
+            let (label, actions) = line.text.split_once('{').ok_or_else(|| line.end_of_line("'{'"))?;
+            let actions = actions.trim_end_matches('}')
+                .split(',')
+                .inspect(|action| targets.push((line, action.split_once(':').map_or(*action, |(_, target)| target))))
+                .map(|action| Instruction::new(line, action))
+                .collect::<Result<_, _>>()?;
+            self.instructions.push(Workflow { label: label.to_string(), actions });
+        }
+        for (line, target) in targets {
+            if target != "A" && target != "R" && !self.instructions.iter().any(|i| i.label == target) {
+                return Err(line.error(target, format!("unknown workflow '{target}'")));
             }
         }
+        if !self.instructions.iter().any(|i| i.label == "in") {
+            return Err(ParseError::new(1, 1, "", "expected a workflow named 'in'"));
+        }
+        for line in &parts {
+            let values: Vec<u32> = line.integers()?;
+            let [x, m, a, s] = values[..] else {
+                return Err(line.error(line.text, format!("expected 4 ratings, found {}", values.len())));
+            };
+            self.parts.push(Part { x, m, a, s, label: "in".to_string() });
+        }
+        Ok(())
     }
 
     fn process_parts(&mut self) -> u32 {
         let mut somme = 0;
         while let Some(mut part) = self.parts.pop() {
-            let instruction = self.instructions.iter().find(|i| i.label == part.label).unwrap();
+            let instruction = self.instructions.iter().find(|i| i.label == part.label).expect("targets are checked when parsing");
             for action in &instruction.actions {
                 if action.correspond(&mut part) {
                     break;
@@ -265,7 +270,7 @@ impl Solver {
         let mut ranges = vec![range];
         let mut somme = 0;
         while let Some(range) = ranges.pop() {
-            let instruction = self.instructions.iter().find(|i| i.label == range.label).unwrap();
+            let instruction = self.instructions.iter().find(|i| i.label == range.label).expect("targets are checked when parsing");
             let mut current_range = range.clone();
             for action in &instruction.actions {
                 if let Some((match_range, left_range)) = action.check_ranges(&current_range) {
@@ -289,12 +294,12 @@ impl Solver {
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
     let mut solver = Solver::new();
     solver.solve_part1(input)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
     let mut solver = Solver::new();
     solver.solve_part2(input)
 }
@@ -306,13 +311,25 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(19114));
+        assert_eq!(result, Ok(19114));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(167409079868000));
+        assert_eq!(result, Ok(167409079868000));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let result = part_one("in{x>10:A,R}\n\n{x=1,m=2,a=@,s=4}");
+        assert_eq!(result.map_err(|e| e.message), Err("expected 4 ratings, found 3".to_string()));
+        let result = part_one("in{y>10:A,R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!(result.map_err(|e| e.column), Err(4));
+        let result = part_two("in{x>10:qq,R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!(result.map_err(|e| (e.column, e.message)), Err((9, "unknown workflow 'qq'".to_string())));
+        let result = part_one("px{x>10:A,R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!(result.map_err(|e| e.message), Err("expected a workflow named 'in'".to_string()));
     }
 
     #[test]
@@ -1,9 +1,14 @@
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::template::dot::{DotGraph, ExportGraph};
+use advent_of_code::template::visualize::Color;
 use crate::ModuleKind::{Broadcaster, Conjunction, FlipFlop, Output};
 
-advent_of_code::solution!(20);
+advent_of_code::solution!(20, graph = Modules);
 
+const RX: usize = 2;
+const MAX_PRESSES: u64 = 1_000_000;
 
 struct PulseQueue {
     queue: VecDeque<Pulse>,
@@ -106,10 +111,8 @@ struct Propagator {
     modules: HashMap<usize, Module>,
     module_names: HashMap<String, usize>,
     number_of_runs: u64,
-    index_36: u64,
-    index_58 : u64,
-    index_16 : u64,
-    index_19 : u64,
+    rx_hub: Option<usize>,
+    feeders: HashMap<usize, u64>,
 }
 
 impl Propagator {
@@ -122,41 +125,37 @@ impl Propagator {
             modules: HashMap::new(),
             module_names: m_names,
             number_of_runs: 0,
-            index_36: 0,
-            index_58: 0,
-            index_16: 0,
-            index_19: 0,
+            rx_hub: None,
+            feeders: HashMap::new(),
         }
     }
-    fn parse_input(&mut self, input: &str) {
-        self.modules.insert(2, Module::new(Output,  Vec::new()));
-        for line in input.lines() {
-            let (f_part, dest_str) = line.split_at(line.find(" -> ").unwrap());
-            let module_id = self.get_module_id(&f_part[1..]);
-            let dests = self.get_dests(&dest_str[4..]);
-            match f_part.chars().next().unwrap() {
-                '%' => {
-                    self.modules.insert(module_id, Module::new(FlipFlop, dests));
-                }
-                '&' => {
-                    self.modules.insert(module_id, Module::new(Conjunction, dests));
-                }
-                'b' => {
-                    self.modules.insert(module_id, Module::new(Broadcaster, dests));
-                }
-                _ => panic!("Invalid line"),
-            }
+    fn parse_input(&mut self, input: &str) -> Result<(), ParseError> {
+        self.modules.insert(RX, Module::new(Output, Vec::new()));
+        for line in parse::lines(input) {
+            let (f_part, dests) = line.arrow()?;
+            let mut chars = f_part.chars();
+            let kind = match chars.next() {
+                Some('%') => FlipFlop,
+                Some('&') => Conjunction,
+                Some('b') => Broadcaster,
+                Some(c) => return Err(line.unexpected(&f_part[..c.len_utf8()])),
+                None => return Err(line.error(f_part, "expected a module name")),
+            };
+            let module_id = self.get_module_id(chars.as_str());
+            let dests = self.get_dests(&dests);
+            self.modules.insert(module_id, Module::new(kind, dests));
         }
         let mut data: HashMap<usize, Vec<usize>> = HashMap::new();
         let keys: Vec<usize> = self.modules.keys().copied().collect();
         for id in keys.clone() {
             let module = self.modules.get_mut(&id).unwrap();
             for dest in module.get_dests() {
-                let sources = data.entry(*dest).or_insert(Vec::new());
+                let sources = data.entry(*dest).or_default();
                 sources.push(id);
             }
         }
         self.update_references(data);
+        Ok(())
     }
 
 
@@ -165,19 +164,20 @@ impl Propagator {
             if id < 3 {
                 continue;
             }
-            let toto = self.modules.get_mut(&id);
-            if toto.is_some() {
-                toto.unwrap().update_references(&sources);
+            if let Some(module) = self.modules.get_mut(&id) {
+                module.update_references(&sources);
             }
         }
     }
 
-    fn get_dests(&mut self, input: &str) -> Vec<usize> {
-        let mut dests = Vec::new();
-        for word in input.split(",") {
-            dests.push(self.get_module_id(word.trim()));
-        }
-        dests
+    fn get_dests(&mut self, names: &[&str]) -> Vec<usize> {
+        names.iter().map(|name| self.get_module_id(name)).collect()
+    }
+
+    fn get_module_name(&self, id: usize) -> &str {
+        self.module_names.iter()
+            .find(|(_, &val)| val == id)
+            .map_or("?", |(name, _)| name.as_str())
     }
 
     fn get_module_id(&mut self, name: &str) -> usize {
@@ -197,17 +197,80 @@ impl Propagator {
             low_tot += low as u64;
             high_tot += high as u64;
         }
-        return high_tot * low_tot;
+        high_tot * low_tot
     }
 
-    fn part_two(&mut self) -> u64 {
-        loop {
+    /// `rx` is fed by a single conjunction, itself fed by conjunctions that each fire
+    /// a high pulse periodically. Returns the hub and its feeders.
+    fn find_rx_feeders(&self) -> Result<(usize, Vec<usize>), String> {
+        let hubs: Vec<usize> = self.modules.iter()
+            .filter(|(_, module)| module.dests.contains(&RX))
+            .map(|(&id, _)| id)
+            .collect();
+        let [hub] = hubs[..] else {
+            return Err(format!("expected exactly one module sending to rx, found {}", hubs.len()));
+        };
+        let hub_module = &self.modules[&hub];
+        if !matches!(hub_module.kind, Conjunction) {
+            return Err(format!("module {} sending to rx is not a conjunction", self.get_module_name(hub)));
+        }
+        let mut feeders: Vec<usize> = hub_module.sources.keys().copied().collect();
+        if feeders.is_empty() {
+            return Err(format!("conjunction {} sending to rx has no inputs", self.get_module_name(hub)));
+        }
+        feeders.sort_unstable();
+        for feeder in &feeders {
+            if !matches!(self.modules[feeder].kind, Conjunction) {
+                return Err(format!("module {} feeding {} is not a conjunction", self.get_module_name(*feeder), self.get_module_name(hub)));
+            }
+        }
+        Ok((hub, feeders))
+    }
+
+    fn part_two(&mut self) -> Result<u64, String> {
+        let (hub, feeders) = self.find_rx_feeders()?;
+        self.rx_hub = Some(hub);
+        self.feeders = feeders.into_iter().map(|feeder| (feeder, 0)).collect();
+        while self.feeders.values().any(|&first_high| first_high == 0) {
+            if self.number_of_runs >= MAX_PRESSES {
+                return Err(format!("feeders of {} did not all send a high pulse within {MAX_PRESSES} presses", self.get_module_name(hub)));
+            }
             self.run_one();
-            if self.index_36 != 0 && self.index_58 != 0 && self.index_16 != 0 && self.index_19 != 0 {
-                break;
+        }
+        Ok(self.feeders.values().fold(1, |acc, &first_high| lcm(acc, first_high)))
+    }
+
+    /// The module network, with flip-flops as boxes and conjunctions as diamonds. When `rx` is
+    /// fed the way `part_two` expects, its hub and the feeders are grouped in a cluster.
+    fn graph(&self) -> DotGraph {
+        let mut graph = DotGraph::directed("modules");
+        graph.attribute("rankdir", "LR");
+        if let Ok((hub, feeders)) = self.find_rx_feeders() {
+            graph.cluster("rx", "rx feeders").color(Color::Magenta);
+            for id in feeders.into_iter().chain([hub, RX]) {
+                graph.node(id).cluster("rx");
+            }
+        }
+        let mut ids: Vec<usize> = self.modules.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            let module = &self.modules[&id];
+            let (name, shape, color) = match module.kind {
+                FlipFlop => (format!("%{}", self.get_module_name(id)), "box", Color::Blue),
+                Conjunction => (format!("&{}", self.get_module_name(id)), "diamond", Color::Yellow),
+                Broadcaster => ("broadcaster".to_string(), "invhouse", Color::Green),
+                // `rx` is only drawn when a module sends to it.
+                Output => continue,
+            };
+            graph.node(id).label(name).shape(shape).color(color);
+            for &dest in &module.dests {
+                if self.modules.get(&dest).is_none_or(|module| matches!(module.kind, Output)) {
+                    graph.node(dest).label(self.get_module_name(dest)).shape("doublecircle").color(Color::Red);
+                }
+                graph.edge(id, dest);
             }
         }
-        return lcm(self.index_36, lcm(self.index_58, lcm(self.index_16, self.index_19)));
+        graph
     }
 
     fn run_one(&mut self) -> (usize, usize) {
@@ -215,29 +278,11 @@ impl Propagator {
         let mut queue = PulseQueue::new();
         queue.push(Pulse { orig: 0, high: false, dest: self.get_module_id("roadcaster") });
         while let Some(pulse) = queue.pop() {
-            if pulse.dest == 20 && pulse.high {
-                match pulse.orig {
-                    36 => {
-                        if self.index_36 == 0 {
-                            self.index_36 = self.number_of_runs ;
-                        }
-                    }
-                    58 => {
-                        if self.index_58 == 0 {
-                            self.index_58 = self.number_of_runs ;
-                        }
-                    }
-                    16 => {
-                        if self.index_16 == 0 {
-                            self.index_16 = self.number_of_runs ;
-                        }
+            if pulse.high && Some(pulse.dest) == self.rx_hub {
+                if let Some(first_high) = self.feeders.get_mut(&pulse.orig) {
+                    if *first_high == 0 {
+                        *first_high = self.number_of_runs;
                     }
-                    19 => {
-                        if self.index_19 == 0 {
-                            self.index_19 = self.number_of_runs ;
-                        }
-                    }
-                    _ => (),
                 }
             }
             if pulse.dest == 1 {
@@ -273,16 +318,26 @@ fn lcm(a: u64, b: u64) -> u64 {
 }
 
 
-pub fn part_one(input: &str) -> Option<u64> {
+struct Modules;
+
+impl ExportGraph for Modules {
+    fn graph(input: &str) -> Result<DotGraph, String> {
+        let mut propagator = Propagator::new();
+        propagator.parse_input(input).map_err(|e| e.to_string())?;
+        Ok(propagator.graph())
+    }
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
     let mut propagator = Propagator::new();
-    propagator.parse_input(input);
-    Some(propagator.part_one())
+    propagator.parse_input(input)?;
+    Ok(propagator.part_one())
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(input: &str) -> Result<u64, String> {
     let mut propagator = Propagator::new();
-    propagator.parse_input(input);
-    Some(propagator.part_two())
+    propagator.parse_input(input).map_err(|e| e.to_string())?;
+    propagator.part_two()
 }
 
 #[cfg(test)]
@@ -292,13 +347,37 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file_part("examples", DAY, 1));
-        assert_eq!(result, Some(32000000));
+        assert_eq!(result, Ok(32000000));
     }
 
     #[test]
     fn test_part_oneb() {
         let result = part_one(&advent_of_code::template::read_file_part("examples", DAY, 2));
-        assert_eq!(result, Some(11687500));
+        assert_eq!(result, Ok(11687500));
     }
 
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file_part("examples", DAY, 3));
+        assert_eq!(result, Ok(21));
+    }
+
+    #[test]
+    fn test_part_two_without_rx() {
+        let result = part_two(&advent_of_code::template::read_file_part("examples", DAY, 1));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_graph() {
+        let graph = Modules::graph(&advent_of_code::template::read_file_part("examples", DAY, 2)).unwrap();
+        assert_eq!((graph.nodes(), graph.edges()), (6, 6));
+        assert!(graph.to_string().contains("[label=\"&inv\", shape=diamond"));
+    }
+
+    #[test]
+    fn test_invalid_module() {
+        let result = part_one("broadcaster -> a\n*a -> b");
+        assert_eq!(result, Err(ParseError::unexpected(2, 1, '*')));
+    }
 }
@@ -1,19 +1,25 @@
-advent_of_code::solution!(21);
+advent_of_code::solution!(21, visualize = Steps);
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use advent_of_code::ParseError;
+use advent_of_code::template::visualize::{Color, Frame, Visualize};
 
-#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
-struct Position {
-    position: (usize, usize),
-    steps: usize,
-}
+const PART_ONE_STEPS: usize = 64;
+const PART_TWO_STEPS: usize = 26501365;
+
+const UNREACHED: usize = usize::MAX;
 
 pub struct Maze {
     data: Vec<Vec<Spot>>,
     xlen: usize,
     ylen: usize,
     first_position: (usize, usize),
-    position_list: Vec<Position>,
+    /// BFS distance from the start for each cell, row-major, `UNREACHED` for rocks and enclosed plots.
+    distances: Vec<usize>,
+    /// Reachable plots by distance parity.
+    parity_counts: [usize; 2],
+    /// Reachable plots by distance parity, only counting those farther than half the grid.
+    corner_counts: [usize; 2],
 }
 
 enum Spot {
@@ -22,117 +28,215 @@ enum Spot {
 }
 
 impl Maze {
-    pub fn new(input: &str) -> Self {
-        let mut data = Vec::new();
-        let mut position = (0, 0);
-        let mut first_position: (usize, usize) = (0, 0);
-        for line in input.lines() {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let mut data: Vec<Vec<Spot>> = Vec::new();
+        let mut first_position = None;
+        for (x, line) in input.lines().enumerate() {
             let mut row = Vec::new();
-            for char in line.chars() {
+            for (y, char) in line.chars().enumerate() {
                 match char {
-                    '#' => {
-                        row.push(Spot::Rock);
-                    }
+                    '#' => row.push(Spot::Rock),
                     '.' => row.push(Spot::Plot),
                     'S' => {
                         row.push(Spot::Plot);
-                        first_position = position;
+                        first_position = Some((x, y));
                     }
-                    _ => panic!("Invalid char"),
+                    _ => return Err(ParseError::unexpected(x + 1, y + 1, char)),
                 }
-                position.1 += 1;
+            }
+            if !data.is_empty() && data[0].len() != row.len() {
+                return Err(ParseError::new(x + 1, 1, line, format!("expected {} plots, found {}", data[0].len(), row.len())));
             }
             data.push(row);
-            position.0 += 1;
-            position.1 = 0;
         }
+        let first_position = first_position.ok_or_else(|| ParseError::new(1, 1, "", "no starting position 'S'"))?;
         let xmax = data.len();
         let ymax = data[0].len();
-        Self {
+        Ok(Self {
             data,
             xlen: xmax,
             ylen: ymax,
             first_position,
-            position_list: Vec::new(),
-        }
+            distances: Vec::new(),
+            parity_counts: [0; 2],
+            corner_counts: [0; 2],
+        })
     }
 
-    fn is_valid_plot(&self, x: i32, y: i32) -> bool {
-        if x < 0 || y < 0 {
-            return false;
-        }
-        if x >= self.xlen as i32 || y >= self.ylen as i32 {
-            return false;
-        }
-        let real_x = (x) as usize;
-        let real_y = (y) as usize;
-        match self.data[real_x][real_y] {
-            Spot::Plot => true,
-            _ => false,
-        }
-    }
-
-    fn get_four_directions(&self, position: &Position) -> Vec<Position> {
-        let mut four_elements : Vec<Position> = Vec::new();
-        let mut new_pos = position.clone();
-        new_pos.steps += 1;
-        new_pos.position.0 += 1;
-        four_elements.push(new_pos.clone());
-        new_pos.position.0 -= 2;
-        four_elements.push(new_pos.clone());
-        new_pos.position.0 += 1;
-        new_pos.position.1 += 1;
-        four_elements.push(new_pos.clone());
-        new_pos.position.1 -= 2;
-        four_elements.push(new_pos.clone());
-        four_elements
+    fn is_valid_plot(&self, x: usize, y: usize) -> bool {
+        x < self.xlen && y < self.ylen && matches!(self.data[x][y], Spot::Plot)
     }
 
     fn move_elf(&mut self) {
-        let mut pile: VecDeque<Position> = VecDeque::new();
-        pile.push_back(Position { position: self.first_position, steps: 0 });
-        while let Some(position) = pile.pop_front() {
-            for position in self.get_four_directions(&position) {
-                if self.is_valid_plot(position.position.0 as i32, position.position.1 as i32) {
-                    if self.position_list.iter().filter(|&x| x.position == position.position).count() > 0 {
-                        continue;
-                    }
-                    self.position_list.push(position);
-                    pile.push_back(position);
+        let half = self.xlen / 2;
+        self.distances = vec![UNREACHED; self.xlen * self.ylen];
+        let mut pile: VecDeque<(usize, usize)> = VecDeque::new();
+        let (x, y) = self.first_position;
+        self.distances[x * self.ylen + y] = 0;
+        pile.push_back((x, y));
+        while let Some((x, y)) = pile.pop_front() {
+            let steps = self.distances[x * self.ylen + y];
+            self.parity_counts[steps % 2] += 1;
+            if steps > half {
+                self.corner_counts[steps % 2] += 1;
+            }
+            // positions off the top/left edge wrap around and are rejected by `is_valid_plot`.
+            for (nx, ny) in [(x + 1, y), (x.wrapping_sub(1), y), (x, y + 1), (x, y.wrapping_sub(1))] {
+                if self.is_valid_plot(nx, ny) && self.distances[nx * self.ylen + ny] == UNREACHED {
+                    self.distances[nx * self.ylen + ny] = steps + 1;
+                    pile.push_back((nx, ny));
                 }
             }
         }
     }
 
-    fn part1(&mut self) -> usize {
-        self.position_list.iter().filter(|&x| x.steps < 65 && x.steps % 2 == 0).count()
+    fn part1(&self, steps: usize) -> usize {
+        self.distances.iter().filter(|&&x| x <= steps && x % 2 == steps % 2).count()
     }
 
-    fn part2(&mut self) -> usize{
-        let n = 202300;
-        let even_corners = self.position_list.iter().filter(|&x| x.steps % 2 == 0 && x.steps > 65).count();
-        let odd_corners = self.position_list.iter().filter(|&x| x.steps % 2 == 1 && x.steps > 65).count();
-        let even = self.position_list.iter().filter(|&k| k.steps % 2 == 1).count();
-        let odd = self.position_list.iter().filter(|&k| k.steps % 2 == 0).count();
-        (n + 1) * (n + 1) * even + n * n * odd - ((n + 1) * odd_corners) + (n * even_corners)
+    /// Diamond-parity formula for the infinite garden. Only valid when the grid is square,
+    /// the start is in its center, `steps` ends exactly on the edge of a tile and, like the
+    /// real inputs, the tile border and the diamond around the start are free of rocks.
+    fn part2(&self, steps: usize) -> Option<usize> {
+        let size = self.xlen;
+        let half = self.first_position.0;
+        if self.ylen != size || size.is_multiple_of(2) || half != size / 2 || self.first_position.1 != half {
+            return None;
+        }
+        if steps < half || !(steps - half).is_multiple_of(size) {
+            return None;
+        }
+        let n = (steps - half) / size;
+        // tiles on the outer ring of the diamond share this parity.
+        let outer = (steps + n) % 2;
+        let (full, corners) = (self.parity_counts, self.corner_counts);
+        Some((n + 1) * (n + 1) * full[outer] + n * n * full[1 - outer]
+            - (n + 1) * corners[outer] + n * corners[1 - outer])
+    }
+
+    /// Brute-force BFS over the infinitely tiled garden.
+    fn part2_brute_force(&self, steps: usize) -> usize {
+        let (xlen, ylen) = (self.xlen as isize, self.ylen as isize);
+        let start = (self.first_position.0 as isize, self.first_position.1 as isize);
+        let mut seen: HashSet<(isize, isize)> = HashSet::from([start]);
+        let mut frontier = vec![start];
+        let mut count = usize::from(steps.is_multiple_of(2));
+        for step in 1..=steps {
+            let mut next = Vec::new();
+            for (x, y) in frontier {
+                for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                    let spot = &self.data[nx.rem_euclid(xlen) as usize][ny.rem_euclid(ylen) as usize];
+                    if matches!(spot, Spot::Plot) && seen.insert((nx, ny)) {
+                        next.push((nx, ny));
+                    }
+                }
+            }
+            if step % 2 == steps % 2 {
+                count += next.len();
+            }
+            frontier = next;
+        }
+        count
     }
 }
 
+/// Plots the elf can stand on after each of the part one steps.
+struct Steps;
+
+impl Visualize for Steps {
+    fn frames(input: &str) -> Result<Vec<Frame>, String> {
+        let mut maze = Maze::new(input).map_err(|e| e.to_string())?;
+        maze.move_elf();
+        let frames = (0..=PART_ONE_STEPS).map(|steps| {
+            let mut frame = Frame::new(maze.xlen, maze.ylen);
+            for x in 0..maze.xlen {
+                for y in 0..maze.ylen {
+                    let distance = maze.distances[x * maze.ylen + y];
+                    let (glyph, color) = match maze.data[x][y] {
+                        Spot::Rock => ('#', Color::Gray),
+                        Spot::Plot if distance <= steps && distance % 2 == steps % 2 => ('O', Color::Green),
+                        Spot::Plot => ('.', Color::Default),
+                    };
+                    frame.set(x, y, glyph, color);
+                }
+            }
+            let (x, y) = maze.first_position;
+            frame.set(x, y, 'S', Color::Yellow);
+            frame.with_caption(format!("step {steps}: {} plots", maze.part1(steps)))
+        });
+        Ok(frames.collect())
+    }
+}
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let mut maze = Maze::new(input);
+pub fn count_garden_plots(input: &str, steps: usize) -> Result<usize, ParseError> {
+    let mut maze = Maze::new(input)?;
     maze.move_elf();
-    Some(maze.part1())
+    Ok(maze.part1(steps))
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut maze = Maze::new(input);
+pub fn count_infinite_garden_plots(input: &str, steps: usize) -> Result<usize, String> {
+    let mut maze = Maze::new(input).map_err(|e| e.to_string())?;
     maze.move_elf();
-    Some(maze.part2())
+    maze.part2(steps).ok_or_else(|| format!("the garden does not fit the diamond-parity formula for {steps} steps"))
+}
+
+pub fn count_infinite_garden_plots_brute_force(input: &str, steps: usize) -> Result<usize, ParseError> {
+    let maze = Maze::new(input)?;
+    Ok(maze.part2_brute_force(steps))
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    count_garden_plots(input, PART_ONE_STEPS)
+}
+
+pub fn part_two(input: &str) -> Result<usize, String> {
+    count_infinite_garden_plots(input, PART_TWO_STEPS)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
 
+    #[test]
+    fn test_part_one() {
+        let result = count_garden_plots(&advent_of_code::template::read_file("examples", DAY), 6);
+        assert_eq!(result, Ok(16));
+    }
 
+    #[test]
+    fn test_visualize() {
+        let frames = Steps::frames(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(frames[6].caption, "step 6: 16 plots");
+    }
+
+    #[test]
+    fn test_part_two_brute_force() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        for (steps, expected) in [(6, 16), (10, 50), (50, 1594), (100, 6536), (500, 167004)] {
+            assert_eq!(count_infinite_garden_plots_brute_force(&input, steps), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_part_two() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        for steps in [27, 38, 49, 60, 104] {
+            assert_eq!(
+                count_infinite_garden_plots(&input, steps).ok(),
+                count_infinite_garden_plots_brute_force(&input, steps).ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_part_two_needs_aligned_steps() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        assert!(count_infinite_garden_plots(&input, 30).is_err());
+    }
+
+    #[test]
+    fn test_missing_start() {
+        let result = count_garden_plots("...\n.#.\n...", 6);
+        assert_eq!(result.map_err(|e| e.message), Err("no starting position 'S'".to_string()));
+    }
 }
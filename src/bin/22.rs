@@ -1,7 +1,11 @@
-advent_of_code::solution!(22);
+advent_of_code::solution!(22, visualize = Chute);
 
-use std::collections::{HashMap, HashSet, VecDeque};
 use advent_of_code::Coord;
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::template::visualize::{Color, Frame, Visualize};
+
+const MAX_FRAMES: usize = 200;
+const BRICK_COLORS: [Color; 6] = [Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan];
 
 #[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 struct Brique {
@@ -11,183 +15,175 @@ struct Brique {
 }
 
 struct Mur {
-    briques: HashMap<usize, Brique>,
-    briques_en_dessous: HashMap<usize, Vec<usize>>,
-    briques_au_dessus: HashMap<usize, Vec<usize>>,
-    max_z: isize,
+    briques: Vec<Brique>,
+    briques_en_dessous: Vec<Vec<usize>>,
+    briques_au_dessus: Vec<Vec<usize>>,
+    /// Indices of the bricks in the order they settled (lowest first).
+    ordre: Vec<usize>,
+    max_x: isize,
+    max_y: isize,
 }
 
 impl Mur {
-    fn new(input: &str) -> Self {
-        let mut briques = HashMap::new();
-        let mut index = 0;
-        let mut max_z = 0;
-        for line in input.lines() {
-            let mut parts = line.split(|c| c == '~' || c == ',');
-            let x1 = parts.next().unwrap().parse().unwrap();
-            let y1 = parts.next().unwrap().parse().unwrap();
-            let z1 = parts.next().unwrap().parse().unwrap();
-            let x2 = parts.next().unwrap().parse().unwrap();
-            let y2 = parts.next().unwrap().parse().unwrap();
-            let z2 = parts.next().unwrap().parse().unwrap();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut briques = Vec::new();
+        let mut max_x = 0;
+        let mut max_y = 0;
+        for line in parse::lines(input) {
+            // coordinates index the height map, so they can't be negative.
+            let [x1, y1, z1, x2, y2, z2] = line.fields::<[usize; 6]>(&['~', ','])?.map(|value| value as isize);
             let brique = Brique {
-                x: Coord(x1, x2),
-                y: Coord(y1, y2),
-                z: Coord(z1, z2),
+                x: Coord(x1.min(x2), x1.max(x2)),
+                y: Coord(y1.min(y2), y1.max(y2)),
+                z: Coord(z1.min(z2), z1.max(z2)),
             };
-            briques.insert(index, brique);
-            max_z = max_z.max(z1);
-            max_z = max_z.max(z2);
-            index += 1;
+            max_x = max_x.max(brique.x.1);
+            max_y = max_y.max(brique.y.1);
+            briques.push(brique);
         };
-        Self {
+        let count = briques.len();
+        Ok(Self {
             briques,
-            briques_en_dessous: HashMap::new(),
-            briques_au_dessus: HashMap::new(),
-            max_z
-        }
+            briques_en_dessous: vec![Vec::new(); count],
+            briques_au_dessus: vec![Vec::new(); count],
+            ordre: Vec::new(),
+            max_x,
+            max_y,
+        })
     }
 
+    /// Drops every brick in a single pass from the lowest one up, keeping for each
+    /// `(x, y)` column the height of its top and the brick owning it.
     fn move_down(&mut self) {
-        let mut moved_briques = true;
-        while moved_briques {
-            moved_briques = false;
-            let mut etage = 1;
-            loop {
-                let mut cases_prises: HashSet<(Coord, Coord)> = HashSet::new();
-                for (_ , brique) in &self.briques {
-                    if brique.z.0 <= etage && brique.z.1 >= etage {
-                        cases_prises.insert((brique.x, brique.y));
+        let width = (self.max_y + 1) as usize;
+        let mut hauteurs: Vec<(isize, Option<usize>)> = vec![(0, None); (self.max_x + 1) as usize * width];
+        self.ordre = (0..self.briques.len()).collect();
+        self.ordre.sort_unstable_by_key(|&index| self.briques[index].z.0);
+        for &index in &self.ordre {
+            let brique = self.briques[index];
+            let colonnes = || (brique.x.0..=brique.x.1)
+                .flat_map(move |x| (brique.y.0..=brique.y.1).map(move |y| x as usize * width + y as usize));
+            let sommet = colonnes().map(|colonne| hauteurs[colonne].0).max().unwrap_or(0);
+            let mut en_dessous = Vec::new();
+            for colonne in colonnes() {
+                if let (hauteur, Some(owner)) = hauteurs[colonne] {
+                    if hauteur == sommet && !en_dessous.contains(&owner) {
+                        en_dessous.push(owner);
                     }
                 }
-                for (_, brique) in &mut self.briques {
-                    if brique.z.0 == etage + 1 {
-                        if !intersect_horizontally(&brique, &cases_prises) {
-                            brique.z.0 -= 1;
-                            brique.z.1 -= 1;
-                            moved_briques = true;
-                        }
-                    }
-                }
-                if etage > self.max_z {
-                    break;
-                }
-                etage += 1;
             }
-        }
-        for (index, brique) in &self.briques {
-            let supporting = self.find_supporting_bricks(&brique);
-            let supported = self.find_supported_bricks(&brique);
-            self.briques_en_dessous.insert(*index, supporting);
-            self.briques_au_dessus.insert(*index, supported);
-        }
-    }
-
-
-    fn find_supported_bricks(&self, brique: &Brique) -> Vec<usize> {
-        let mut  res = Vec::new();
-        for (index, brique2) in &self.briques {
-            if brique2.z.0 == brique.z.1 + 1 &&
-                intersect(&(brique2.x, brique2.y), &(brique.x, brique.y)) {
-                res.push(*index);
+            let chute = brique.z.0 - sommet - 1;
+            let brique = &mut self.briques[index];
+            brique.z = Coord(brique.z.0 - chute, brique.z.1 - chute);
+            for colonne in colonnes() {
+                hauteurs[colonne] = (brique.z.1, Some(index));
             }
-        }
-        res
-    }
-
-    fn find_supporting_bricks(&self, brique: &Brique) -> Vec<usize> {
-        let mut  res = Vec::new();
-        for (index, brique2) in &self.briques {
-            if brique2.z.1 == brique.z.0 -1 &&
-                intersect(&(brique2.x, brique2.y), &(brique.x, brique.y)) {
-                res.push(*index);
+            for &owner in &en_dessous {
+                self.briques_au_dessus[owner].push(index);
             }
+            self.briques_en_dessous[index] = en_dessous;
         }
-        res
     }
 
     fn count_movable(&self) -> usize {
-        self.get_movable().len()
-    }
-
-    fn get_movable(&self) -> Vec<usize> {
-        let mut res = Vec::new();
-        for (index, _) in &self.briques {
-            if self.briques_au_dessus[index].len() == 0 {
-                res.push(*index);
-            } else {
-                let mut moveable = true;
-                for index2 in &self.briques_au_dessus[&index.clone()] {
-                    if self.briques_en_dessous[&index2].len() == 1 {
-                        moveable = false;
-                        break;
-                    }
-                }
-                if moveable {
-                    res.push(*index);
-                }
-            }
-        }
-        res
+        (0..self.briques.len())
+            .filter(|&index| {
+                self.briques_au_dessus[index]
+                    .iter()
+                    .all(|&dessus| self.briques_en_dessous[dessus].len() > 1)
+            })
+            .count()
     }
 
+    /// A brick falls when another one is removed exactly when that other brick dominates it
+    /// in the support graph rooted at the ground. The immediate dominator of a brick is the
+    /// common ancestor of its supports, so the answer is the sum of the brick depths in the
+    /// dominator tree.
     fn count_would_fall(&self) -> usize {
-        let movables = self.get_movable();
+        // `None` stands for the ground.
+        let mut dominateur: Vec<Option<usize>> = vec![None; self.briques.len()];
+        let mut profondeur: Vec<usize> = vec![0; self.briques.len()];
+        let depth = |node: Option<usize>, profondeur: &[usize]| node.map_or(0, |i| profondeur[i]);
         let mut count = 0;
-        for (index, _) in &self.briques {
-            if movables.contains(index) {
+        for &index in &self.ordre {
+            let mut supports = self.briques_en_dessous[index].iter().map(|&i| Some(i));
+            let Some(mut ancetre) = supports.next() else {
+                profondeur[index] = 1;
                 continue;
-            }
-            let mut fallen = Vec::new();
-            let mut pile = VecDeque::from(vec![*index]);
-            while  let  Some(current_index) = pile.pop_front() {
-                fallen.push(current_index);
-                for i in self.briques_au_dessus[&current_index].iter() {
-                    let mut fall = true;
-                    for j in self.briques_en_dessous[i].iter() {
-                        if !fallen.contains(j) {
-                            fall = false;
-                            break;
-                        }
-                    }
-                    if fall {
-                        pile.push_back(*i);
+            };
+            for mut autre in supports {
+                while ancetre != autre {
+                    if depth(ancetre, &profondeur) >= depth(autre, &profondeur) {
+                        ancetre = ancetre.and_then(|i| dominateur[i]);
+                    } else {
+                        autre = autre.and_then(|i| dominateur[i]);
                     }
                 }
             }
-            count += fallen.len() - 1;
+            dominateur[index] = ancetre;
+            profondeur[index] = depth(ancetre, &profondeur) + 1;
+            count += profondeur[index] - 1;
         }
         count
     }
 }
 
-fn intersect(coords1: &(Coord, Coord), coords2: &(Coord, Coord)) -> bool {
-    if coords2.0.0 <= coords1.0.1 && coords2.0.1 >= coords1.0.0 && coords2.1.0 <= coords1.1.1 && coords2.1.1 >= coords1.1.0 {
-        return true;
+/// Bricks settling one after the other, seen from the front (x, z) and the side (y, z).
+struct Chute;
+
+impl Chute {
+    fn frame(briques: &[Brique], max_x: isize, max_y: isize, max_z: isize) -> Frame {
+        let (width_x, width_y) = ((max_x + 1) as usize, (max_y + 1) as usize);
+        let mut frame = Frame::new(max_z as usize + 1, width_x + 1 + width_y);
+        for col in 0..width_x + 1 + width_y {
+            frame.set(max_z as usize, col, '-', Color::Gray);
+        }
+        for (index, brique) in briques.iter().enumerate() {
+            let color = BRICK_COLORS[index % BRICK_COLORS.len()];
+            for z in brique.z.0..=brique.z.1 {
+                let row = (max_z - z) as usize;
+                for x in brique.x.0..=brique.x.1 {
+                    frame.set(row, x as usize, '#', color);
+                }
+                for y in brique.y.0..=brique.y.1 {
+                    frame.set(row, width_x + 1 + y as usize, '#', color);
+                }
+            }
+        }
+        frame
     }
-    false
 }
 
-fn intersect_horizontally(brique1: &Brique, cases: &HashSet<(Coord, Coord)>) -> bool {
-    for coords in cases {
-        if intersect(&(brique1.x, brique1.y), coords) {
-            return true;
+impl Visualize for Chute {
+    fn frames(input: &str) -> Result<Vec<Frame>, String> {
+        let mut mur = Mur::new(input).map_err(|e| e.to_string())?;
+        let depart = mur.briques.clone();
+        mur.move_down();
+        let max_z = depart.iter().map(|brique| brique.z.1).max().unwrap_or(0);
+        let stride = mur.ordre.len().div_ceil(MAX_FRAMES).max(1);
+        let mut briques = depart;
+        let mut frames = vec![Self::frame(&briques, mur.max_x, mur.max_y, max_z).with_caption("snapshot")];
+        for (settled, &index) in mur.ordre.iter().enumerate() {
+            briques[index] = mur.briques[index];
+            if (settled + 1) % stride == 0 || settled + 1 == mur.ordre.len() {
+                let frame = Self::frame(&briques, mur.max_x, mur.max_y, max_z);
+                frames.push(frame.with_caption(format!("{} of {} bricks settled", settled + 1, mur.ordre.len())));
+            }
         }
+        Ok(frames)
     }
-    false
 }
 
-
-pub fn part_one(input: &str) -> Option<usize> {
-    let mut mur = Mur::new(input);
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let mut mur = Mur::new(input)?;
     mur.move_down();
-    Some(mur.count_movable())
+    Ok(mur.count_movable())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut mur = Mur::new(input);
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let mut mur = Mur::new(input)?;
     mur.move_down();
-    Some(mur.count_would_fall())
+    Ok(mur.count_would_fall())
 }
 
 #[cfg(test)]
@@ -197,12 +193,26 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(5));
+        assert_eq!(result, Ok(5));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(7));
+        assert_eq!(result, Ok(7));
+    }
+
+    #[test]
+    fn test_visualize() {
+        let frames = Chute::frames(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(frames.len(), 8);
+        assert_eq!(frames[7].get(8, 1).map(|cell| cell.glyph), Some('#'));
+        assert_eq!(frames[7].get(3, 0).map(|cell| cell.glyph), Some(' '));
+    }
+
+    #[test]
+    fn test_invalid_brick() {
+        assert_eq!(part_one("1,0,1~1,-2,1"), Err(ParseError::unexpected(1, 9, "-2")));
+        assert_eq!(part_one("1,0,1~1,2").map_err(|e| e.column), Err(10));
     }
 }
@@ -1,8 +1,9 @@
-advent_of_code::solution!(23);
+advent_of_code::solution!(23, graph = Junctions);
 
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::collections::{HashMap, VecDeque};
 use advent_of_code::{Coord, Direction};
+use advent_of_code::template::dot::{DotGraph, ExportGraph};
+use advent_of_code::template::visualize::Color;
 
 #[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 struct Path {
@@ -67,7 +68,7 @@ impl Maze {
     }
 
     pub fn follow_path(&mut self, start: Coord, direction: Direction) -> Option<Path> {
-        let mut current_position = start.clone();
+        let mut current_position = start;
         let mut current_direction = direction;
         let mut distance = 0;
         while self.is_valid_plot(current_position, current_direction) {
@@ -79,7 +80,7 @@ impl Maze {
                     possibilities.push(next_direction);
                 }
             }
-            if possibilities.len() == 0 {
+            if possibilities.is_empty() {
                 return None;
             } else if possibilities.len() == 1 {
                 let next_position = current_position.go(possibilities[0]);
@@ -119,10 +120,10 @@ impl Maze {
         }
         let o = self.maze[origin.0 as usize][origin.1 as usize];
         match o {
-            SLOPE_NORTH => { if direction != Direction::Up { return false; } },
-            SLOPE_SOUTH => { if direction != Direction::Down { return false; } },
-            SLOPE_WEST => { if direction != Direction::Left { return false; } },
-            SLOPE_EAST => { if direction != Direction::Right { return false; } },
+            SLOPE_NORTH if direction != Direction::Up => { return false; },
+            SLOPE_SOUTH if direction != Direction::Down => { return false; },
+            SLOPE_WEST if direction != Direction::Left => { return false; },
+            SLOPE_EAST if direction != Direction::Right => { return false; },
             _ => {},
         }
         match direction {
@@ -133,115 +134,145 @@ impl Maze {
         }
     }
 
-    pub fn solve_part1(&self) -> usize {
-        let mut max_distance = 0;
-        let mut heap: BinaryHeap<Chemin> = BinaryHeap::new();
-        heap.push(Chemin::new(self.pathes[0]));
-        let mut best_distances =  HashMap::new();
-
-        while let Some(chemin) = heap.pop() {
-            for path in self.pathes.iter().filter(|&x| x.start == chemin.end) {
-                let mut new_chemin = chemin.clone();
-                if !new_chemin.add_path(*path) {
-                    continue;
-                }
-                let best_distance = best_distances.entry(path.end).or_insert(0);
-                if *best_distance > new_chemin.distance {
-                    continue;
-                } else {
-                    *best_distance = new_chemin.distance;
-                }
-                if new_chemin.end.0 == self.maze.len() as isize - 1 {
-                    max_distance = std::cmp::max(max_distance, new_chemin.distance);
-                    continue;
-                }
-                heap.push(new_chemin);
+    /// Compresses the path segments into a graph over the junctions, the start and the end.
+    pub fn build_graph(&self) -> Option<Graph> {
+        let mut ids: HashMap<Coord, usize> = HashMap::new();
+        let mut edges: Vec<Vec<(usize, usize)>> = Vec::new();
+        let mut get_id = |coord: Coord, edges: &mut Vec<Vec<(usize, usize)>>| {
+            *ids.entry(coord).or_insert_with(|| {
+                edges.push(Vec::new());
+                edges.len() - 1
+            })
+        };
+        let start = get_id(self.pathes.first()?.start, &mut edges);
+        let mut end = None;
+        for path in &self.pathes {
+            let from = get_id(path.start, &mut edges);
+            let to = get_id(path.end, &mut edges);
+            if !edges[from].contains(&(to, path.distance)) {
+                edges[from].push((to, path.distance));
+            }
+            if path.end.0 == self.maze.len() as isize - 1 {
+                end = Some(to);
             }
         }
-        max_distance
+        let end = end?;
+        let mut best_edge = vec![0; edges.len()];
+        for &(to, distance) in edges.iter().flatten() {
+            best_edge[to] = best_edge[to].max(distance);
+        }
+        let to_end: Vec<usize> = (0..edges.len())
+            .filter(|&node| edges[node].iter().any(|&(to, _)| to == end))
+            .collect();
+        let last_junction = if let [node] = to_end[..] { Some(node) } else { None };
+        Some(Graph { edges, best_edge, start, end, last_junction })
     }
-    pub fn solve_part2(&self) -> usize {
-        let mut max_distance = 0;
-        let mut heap: BinaryHeap<Chemin> = BinaryHeap::new();
-        heap.push(Chemin::new(self.pathes[0]));
-        let mut best_distances =  HashMap::new();
+}
 
-        while let Some(chemin) = heap.pop() {
-            for path in self.pathes.iter().filter(|&x| x.start == chemin.end) {
-                let mut new_chemin = chemin.clone();
-                if !new_chemin.add_path(*path) {
-                    continue;
-                }
-                let best_for_end = best_distances.entry(path.end).or_insert(HashMap::new());
-                let best_for_start = best_for_end.entry(path.start).or_insert(0);
-                if *best_for_start > new_chemin.distance {
-                    continue;
-                } else {
-                    *best_for_start = new_chemin.distance;
-                }
-                if new_chemin.end.0 == self.maze.len() as isize - 1 {
-                    max_distance = std::cmp::max(max_distance, new_chemin.distance);
-                    continue;
-                }
-                heap.push(new_chemin);
+struct Graph {
+    /// Outgoing `(node, distance)` edges of each node.
+    edges: Vec<Vec<(usize, usize)>>,
+    /// Longest edge entering each node.
+    best_edge: Vec<usize>,
+    start: usize,
+    end: usize,
+    /// Only node leading to the end, if there is a single one: once there, going anywhere
+    /// else would cut the end off.
+    last_junction: Option<usize>,
+}
+
+impl Graph {
+    /// Exhaustive search of the longest simple path from start to end.
+    pub fn longest_path(&self) -> Option<usize> {
+        let potential = self.best_edge.iter().sum::<usize>() - self.best_edge[self.start];
+        let mut best = None;
+        let mut visited = Visited::new(self.edges.len());
+        visited.insert(self.start);
+        self.explore(self.start, &mut visited, 0, potential, &mut best);
+        best
+    }
+
+    /// `potential` bounds what is left to gain: every further step enters an unvisited node,
+    /// through an edge no longer than the longest one entering it.
+    fn explore(&self, node: usize, visited: &mut Visited, distance: usize, potential: usize, best: &mut Option<usize>) {
+        if node == self.end {
+            *best = Some(best.map_or(distance, |b| b.max(distance)));
+            return;
+        }
+        if best.is_some_and(|b| distance + potential <= b) {
+            return;
+        }
+        for &(next, length) in &self.edges[node] {
+            if self.last_junction == Some(node) && next != self.end {
+                continue;
+            }
+            if !visited.contains(next) {
+                visited.insert(next);
+                self.explore(next, visited, distance + length, potential - self.best_edge[next], best);
+                visited.remove(next);
             }
         }
-        max_distance
     }
 }
 
-#[derive( Clone, Eq, PartialEq)]
-struct Chemin {
-    pathes: Vec<Path>,
-    distance: usize,
-    end: Coord,
-}
+/// Bitset of the nodes on the current path, one bit per node however many junctions there are.
+struct Visited(Vec<u64>);
 
-impl Chemin {
-    fn new(path: Path) -> Self {
-        Self {
-            pathes: Vec::from([path]),
-            distance: path.distance,
-            end: path.end,
-        }
+impl Visited {
+    fn new(nodes: usize) -> Self {
+        Self(vec![0; nodes.div_ceil(u64::BITS as usize)])
     }
 
-    fn add_path(&mut self, path: Path) -> bool {
-        if self.pathes.iter().filter(|&x| x.end == path.end || x.start == path.end ).count() > 0 {
-            return false;
-        }
-        self.pathes.push(path);
-        self.distance += path.distance;
-        self.end = path.end;
-        true
+    fn contains(&self, node: usize) -> bool {
+        self.0[node / 64] & (1 << (node % 64)) != 0
     }
 
-}
+    fn insert(&mut self, node: usize) {
+        self.0[node / 64] |= 1 << (node % 64);
+    }
 
-impl Ord for Chemin {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.distance.cmp(&other.distance)
+    fn remove(&mut self, node: usize) {
+        self.0[node / 64] &= !(1 << (node % 64));
     }
 }
 
-impl PartialOrd for Chemin {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// The junctions of the maze, linked by the length of the paths between them. Paths go one
+/// way only when they cross a slope.
+struct Junctions;
+
+impl ExportGraph for Junctions {
+    fn graph(input: &str) -> Result<DotGraph, String> {
+        let mut maze = Maze::new(true);
+        maze.read_input(input);
+        let start = maze.pathes.first().ok_or("the maze has no path from the top row")?.start;
+        let name = |coord: Coord| format!("{},{}", coord.0, coord.1);
+        let mut graph = DotGraph::directed("junctions");
+        graph.node(name(start)).label(format!("start\n{}", name(start))).color(Color::Green);
+        let mut seen = Vec::new();
+        for path in &maze.pathes {
+            if seen.contains(&(path.start, path.end)) {
+                continue;
+            }
+            seen.push((path.start, path.end));
+            if path.end.0 == maze.maze.len() as isize - 1 {
+                graph.node(name(path.end)).label(format!("end\n{}", name(path.end))).color(Color::Red);
+            }
+            graph.edge(name(path.start), name(path.end)).label(path.distance);
+        }
+        Ok(graph)
     }
 }
 
-
 pub fn part_one(input: &str) -> Option<usize> {
     let mut maze = Maze::new(true);
     maze.read_input(input);
-    Some(maze.solve_part1())
+    maze.build_graph()?.longest_path()
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     let mut maze = Maze::new(false);
     maze.read_input(input);
-    // low 5538
-    Some(maze.solve_part2())
+    maze.build_graph()?.longest_path()
 }
 
 #[cfg(test)]
@@ -259,4 +290,20 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(154));
     }
+
+    #[test]
+    fn test_many_junctions() {
+        // two parallel edges between each of 100 nodes in a row, the longer one counts.
+        let nodes = 100;
+        let edges = (0..nodes).map(|node| if node + 1 < nodes { vec![(node + 1, 1), (node + 1, 2)] } else { vec![] }).collect();
+        let graph = Graph { edges, best_edge: vec![2; nodes], start: 0, end: nodes - 1, last_junction: None };
+        assert_eq!(graph.longest_path(), Some(2 * (nodes - 1)));
+    }
+
+    #[test]
+    fn test_graph() {
+        let graph = Junctions::graph(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!((graph.nodes(), graph.edges()), (6, 8));
+        assert!(graph.to_string().contains("\"0,1\" -> \"5,3\" [label=\"15\"]"));
+    }
 }
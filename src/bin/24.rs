@@ -1,6 +1,9 @@
-use std::collections::HashSet;
 advent_of_code::solution!(24);
 
+use advent_of_code::parse::{self, ParseError};
+
+const TEST_AREA: (f64, f64) = (200000000000000.0, 400000000000000.0);
+
 #[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 struct Point {
     x: i64,
@@ -9,24 +12,11 @@ struct Point {
 }
 
 impl Point {
-    pub fn add(&self, other: &Self) -> Self {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            z: self.z + other.z,
-        }
-    }
-
-    pub fn mul(&self, t: i64) -> Self {
-        Self {
-            x: self.x * t,
-            y: self.y * t,
-            z: self.z * t,
-        }
+    fn to_i128(self) -> [i128; 3] {
+        [self.x as i128, self.y as i128, self.z as i128]
     }
 }
 
-
 #[derive(Clone, Copy, Debug)]
 struct Grelon {
     coordinates: Point,
@@ -60,11 +50,6 @@ impl Grelon {
         }
         None
     }
-
-
-    pub fn position(&self, t: i64) -> Point {
-        self.coordinates.add(&self.velocity.mul(t))
-    }
 }
 
 fn intersection(a: f64, b: f64, c: f64, d: f64) -> Option<(f64, f64)> {
@@ -77,42 +62,77 @@ fn intersection(a: f64, b: f64, c: f64, d: f64) -> Option<(f64, f64)> {
     }
 }
 
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn sub(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [i128; 3], b: [i128; 3]) -> Option<[i128; 3]> {
+    let component = |i: usize, j: usize| a[i].checked_mul(b[j])?.checked_sub(a[j].checked_mul(b[i])?);
+    Some([component(1, 2)?, component(2, 0)?, component(0, 1)?])
+}
+
+/// Exact Gauss-Jordan elimination over `i128` on an augmented system of `n` unknowns.
+/// Rows are reduced by their gcd after each step to keep the numbers small. Returns each
+/// unknown as a `(numerator, denominator)` pair, or `None` when the system is singular,
+/// inconsistent or overflows.
+fn solve_exact(mut rows: Vec<Vec<i128>>, n: usize) -> Option<Vec<(i128, i128)>> {
+    for col in 0..n {
+        let pivot = (col..rows.len()).find(|&r| rows[r][col] != 0)?;
+        rows.swap(col, pivot);
+        for r in 0..rows.len() {
+            if r == col || rows[r][col] == 0 {
+                continue;
+            }
+            let (p, f) = (rows[col][col], rows[r][col]);
+            let mut row = rows[r].iter()
+                .zip(&rows[col])
+                .map(|(&v, &w)| v.checked_mul(p)?.checked_sub(w.checked_mul(f)?))
+                .collect::<Option<Vec<i128>>>()?;
+            let g = row.iter().fold(0, |acc, &v| gcd(acc, v));
+            if g > 1 {
+                row.iter_mut().for_each(|v| *v /= g);
+            }
+            rows[r] = row;
+        }
+    }
+    if rows[n..].iter().any(|row| row[n] != 0) {
+        return None;
+    }
+    Some((0..n).map(|i| {
+        let (num, den) = (rows[i][n], rows[i][i]);
+        let g = gcd(num, den) * den.signum();
+        (num / g, den / g)
+    }).collect())
+}
+
 struct Solver {
     data: Vec<Grelon>,
-    y_velocity: i64,
-    start_y: i64,
 }
 
 impl Solver {
     pub fn new() -> Self {
         Self {
             data: Vec::new(),
-            y_velocity: 1,
-            start_y: 13,
         }
     }
 
-    pub fn read_input(&mut self, input: &str) {
-        let mut unique_y = HashSet::new();
-        for line in input.lines() {
-            let parts: Vec<&str> = line.split(|c| c == ',' || c == '@').collect();
-            let x = parts[0].trim().parse::<i64>().unwrap();
-            let y = parts[1].trim().parse::<i64>().unwrap();
-            let z = parts[2].trim().parse::<i64>().unwrap();
-            let vx = parts[3].trim().parse::<i64>().unwrap();
-            let vy = parts[4].trim().parse::<i64>().unwrap();
-            let vz = parts[5].trim().parse::<i64>().unwrap();
+    pub fn read_input(&mut self, input: &str) -> Result<(), ParseError> {
+        for line in parse::lines(input) {
+            let [x, y, z, vx, vy, vz] = line.fields::<[i64; 6]>(&[',', '@'])?;
             self.data.push(Grelon::new(Point { x, y, z }, Point { x: vx, y: vy, z: vz }));
-            if unique_y.contains(&(y, vy)) {
-                self.start_y = y;
-                self.y_velocity = vy;
-            } else {
-                unique_y.insert((y, vy));
-            }
         }
+        Ok(())
     }
 
-    pub fn solve_part1(&self, min_area: f64, max_area: f64) -> Option<u32> {
+    pub fn solve_part1(&self, min_area: f64, max_area: f64) -> u32 {
         let mut count = 0;
         for i in 0..self.data.len() {
             let grelon1 = &self.data[i];
@@ -125,60 +145,85 @@ impl Solver {
                 }
             }
         }
-        Some(count)
+        count
     }
 
-    fn when_will_it_collide(&self, grelon: &Grelon) -> Option<i64> {
-        if grelon.coordinates.y == self.start_y {
+    /// Solves for the rock in the frame of `reference`, where that hailstone stands still at
+    /// the origin and the rock's path therefore goes through the origin. Pairing `reference`
+    /// with each of `others` gives the plane holding that hailstone's path and the origin; the
+    /// rock's direction lies in both planes. Each collision time then comes from the linear
+    /// system `p + t * v = s * direction`.
+    fn throw_rock(&self, reference: &Grelon, others: [&Grelon; 2]) -> Option<i64> {
+        let origin = reference.coordinates.to_i128();
+        let drift = reference.velocity.to_i128();
+        let relative = others.map(|g| (sub(g.coordinates.to_i128(), origin), sub(g.velocity.to_i128(), drift)));
+        let normals = [cross(relative[0].0, relative[0].1)?, cross(relative[1].0, relative[1].1)?];
+        let direction = cross(normals[0], normals[1])?;
+        let g = direction.iter().fold(0, |acc, &v| gcd(acc, v));
+        if g == 0 {
             return None;
         }
-        let x = intersection(self.y_velocity as f64, self.start_y as f64, grelon.velocity.y as f64, grelon.coordinates.y as f64);
-        if let Some((x, _)) = x {
-            if x < 0.0 {
+        let direction = direction.map(|v| v / g);
+
+        let mut collisions = Vec::with_capacity(2);
+        for (position, velocity) in relative {
+            let rows = (0..3).map(|k| vec![velocity[k], -direction[k], -position[k]]).collect();
+            let solution = solve_exact(rows, 2)?;
+            let [(time, 1), (scale, 1)] = solution[..] else {
                 return None;
-            }
-            if x.fract() != 0.0 {
+            };
+            collisions.push((time, scale));
+        }
+        let (t1, s1) = collisions[0];
+        let (t2, s2) = collisions[1];
+        if t1 == t2 {
+            return None;
+        }
+        let mut start = 0;
+        for k in 0..3 {
+            let travel = (s2 - s1) * direction[k];
+            if travel % (t2 - t1) != 0 {
                 return None;
             }
-            return Some(x as i64);
-        } else {
-            return None;
+            let speed = travel / (t2 - t1);
+            start += s1 * direction[k] - t1 * speed + origin[k];
         }
+        i64::try_from(start).ok()
     }
 
-
     pub fn solve_part2(&self) -> Option<i64> {
-        let mut positions = Vec::new();
-        while positions.len() < 2 {
-            for grelon in &self.data {
-                if let Some(time) = self.when_will_it_collide(grelon) {
-                    let x = grelon.position(time).x;
-                    let z = grelon.position(time).z;
-                    positions.push((time, x, z));
+        // any three hailstones in general position pin the rock down, try further ones on degenerate picks.
+        let candidates = &self.data[..self.data.len().min(8)];
+        for (i, reference) in candidates.iter().enumerate() {
+            for j in 0..candidates.len() {
+                for k in j + 1..candidates.len() {
+                    if i == j || i == k {
+                        continue;
+                    }
+                    if let Some(result) = self.throw_rock(reference, [&candidates[j], &candidates[k]]) {
+                        return Some(result);
+                    }
                 }
             }
         }
-        let ecart_temps = positions[1].0 - positions[0].0;
-        let ecart_x = positions[1].1 - positions[0].1;
-        let ecart_z = positions[1].2 - positions[0].2;
-        let velocity_x = ecart_x as f64 / ecart_temps as f64;
-        let velocity_z = ecart_z as f64 / ecart_temps as f64;
-        let x = positions[0].1 - (positions[0].0 as f64 * velocity_x) as i64;
-        let z = positions[0].2 - (positions[0].0 as f64 * velocity_z) as i64;
-        Some(x + z + self.start_y)
+        None
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
+pub fn count_intersections(input: &str, min_area: f64, max_area: f64) -> Result<u32, ParseError> {
     let mut solver = Solver::new();
-    solver.read_input(input);
-    solver.solve_part1(200000000000000.0, 400000000000000.0)
+    solver.read_input(input)?;
+    Ok(solver.solve_part1(min_area, max_area))
 }
 
-pub fn part_two(input: &str) -> Option<i64> {
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    count_intersections(input, TEST_AREA.0, TEST_AREA.1)
+}
+
+pub fn part_two(input: &str) -> Result<i64, String> {
     let mut solver = Solver::new();
-    solver.read_input(input);
-    solver.solve_part2()
+    solver.read_input(input).map_err(|e| e.to_string())?;
+    solver.solve_part2().ok_or_else(|| "no rock trajectory goes through the first hailstones".to_string())
 }
 
 #[cfg(test)]
@@ -194,19 +239,19 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let input = advent_of_code::template::read_file("examples", DAY);
-        let mut solver = Solver::new();
-        solver.read_input(&input);
-        let result = solver.solve_part1(7.0, 27.0);
-        assert_eq!(result, Some(2));
+        let result = count_intersections(&advent_of_code::template::read_file("examples", DAY), 7.0, 27.0);
+        assert_eq!(result, Ok(2));
     }
 
     #[test]
     fn test_part_two() {
-        let input = advent_of_code::template::read_file("examples", DAY);
-        let mut solver = Solver::new();
-        solver.read_input(&input);
-        let result = solver.solve_part2();
-        assert_eq!(result, Some(47));
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(47));
+    }
+
+    #[test]
+    fn test_invalid_hailstone() {
+        let result = part_one("19, 13, 30 @ -2,  1, -2\n18, 19, 22 @ -1, -1, x2");
+        assert_eq!(result, Err(ParseError::unexpected(2, 22, "x2")));
     }
 }
@@ -1,9 +1,8 @@
 use std::collections::{ HashSet};
-advent_of_code::solution!(25);
+advent_of_code::solution!(25, graph = Wires);
 
-use std::fs::File;
-use std::io::BufWriter;
-use std::io::prelude::*;
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::template::dot::{DotGraph, ExportGraph};
 
 #[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 struct Wire {
@@ -28,30 +27,23 @@ impl Solver {
         }
     }
 
-    fn solve_part1(&mut self, input: &str) -> std::io::Result<usize> {
-        let file = File::create("wires.dot")?;
-        let mut writer = BufWriter::new(file);
-        writeln!(writer, "graph wires {{")?;
-        for line in input.lines() {
-            let mut parts = line.split(":");
-            writeln!(writer, "{} -- {{ {} }}", parts.next().unwrap(), parts.next().unwrap())?;
-        }
-        writeln!(writer, "}}")?;
-        //println!("Wrote wires.dot .. Use neato -Tsvg wires.dot > wires.svg to generate a picture");
-        for line in input.lines() {
-            let mut parts = line.split_whitespace();
-            let lhs = parts.next().unwrap().strip_suffix(':').unwrap();
-            let c1 = usize::from_str_radix(lhs, 36).unwrap();
-            while let Some(c) = parts.next() {
-                let c2 = usize::from_str_radix(c, 36).unwrap();
+    fn read_input(&mut self, input: &str) -> Result<(), ParseError> {
+        for line in parse::lines(input) {
+            let (lhs, components) = line.key_values()?;
+            let c1 = line.parse_radix(lhs, 36)?;
+            for c in components {
+                let c2 = line.parse_radix(c, 36)?;
                 let wire = Wire { c1, c2 };
                 self.data.push(wire);
             }
         }
-        //println!("Found {} wires", self.data.len());
+        Ok(())
+    }
+
+    fn solve_part1(&mut self) -> usize {
         self.add_to_group_1(usize::from_str_radix("lms", 36).unwrap());
         self.add_to_group_2(usize::from_str_radix("tmc", 36).unwrap());
-        return Ok(self.group1.len() * self.group2.len());
+        self.group1.len() * self.group2.len()
     }
 
     fn add_to_group_1(&mut self, val: usize) {
@@ -98,9 +90,28 @@ impl Solver {
 
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+/// The wiring diagram. Render it with `neato -Tsvg wires.dot > wires.svg` to spot the three
+/// wires to cut.
+struct Wires;
+
+impl ExportGraph for Wires {
+    fn graph(input: &str) -> Result<DotGraph, String> {
+        let mut graph = DotGraph::undirected("wires");
+        graph.attribute("layout", "neato");
+        for line in parse::lines(input) {
+            let (lhs, components) = line.key_values().map_err(|e| e.to_string())?;
+            for rhs in components {
+                graph.edge(lhs, rhs);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
     let mut solver = Solver::new();
-    Some(solver.solve_part1(input).unwrap())
+    solver.read_input(input)?;
+    Ok(solver.solve_part1())
 }
 
 pub fn part_two(_input: &str) -> Option<usize> {
@@ -109,4 +120,12 @@ pub fn part_two(_input: &str) -> Option<usize> {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph() {
+        let graph = Wires::graph(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!((graph.nodes(), graph.edges()), (15, 33));
+        assert!(graph.to_string().starts_with("graph \"wires\" {\n    layout=\"neato\";\n"));
+    }
 }
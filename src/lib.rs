@@ -1,5 +1,8 @@
+pub mod parse;
 pub mod template;
 
+pub use parse::ParseError;
+
 
 
 #[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
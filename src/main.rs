@@ -1,4 +1,4 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{all, download, read, readme, scaffold, solve, status, time, visualize, watch};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,9 +7,13 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
+    use advent_of_code::template::commands::time;
     use advent_of_code::template::Day;
     use std::process;
 
+    /// Percentage by which a part may slow down before `time --compare` flags it.
+    const DEFAULT_THRESHOLD: f64 = 10.0;
+
     pub enum AppArguments {
         Download {
             day: Day,
@@ -17,6 +21,9 @@ mod args {
         Read {
             day: Day,
         },
+        Readme {
+            solved_at: bool,
+        },
         Scaffold {
             day: Day,
             download: bool,
@@ -27,6 +34,12 @@ mod args {
             time: bool,
             dhat: bool,
             submit: Option<u8>,
+            record: bool,
+            export_graph: Option<String>,
+        },
+        Status {
+            json: bool,
+            tests: bool,
         },
         All {
             release: bool,
@@ -35,6 +48,21 @@ mod args {
         Time {
             all: bool,
             day: Option<Day>,
+            options: time::Options,
+        },
+        ListBaselines,
+        DeleteBaseline {
+            name: String,
+        },
+        Visualize {
+            day: Day,
+            fps: Option<u32>,
+            gif: Option<String>,
+            scale: Option<u32>,
+        },
+        Watch {
+            day: Day,
+            release: bool,
         },
         #[cfg(feature = "today")]
         Today,
@@ -49,11 +77,30 @@ mod args {
                 time: args.contains("--time"),
             },
             Some("time") => {
-                let all = args.contains("--all");
+                if args.contains("--list-baselines") {
+                    AppArguments::ListBaselines
+                } else if let Some(name) = args.opt_value_from_str("--delete-baseline")? {
+                    AppArguments::DeleteBaseline { name }
+                } else {
+                    let all = args.contains("--all");
+                    let compare = args.contains("--compare");
+                    let threshold = args.opt_value_from_str("--threshold")?;
+                    let options = time::Options {
+                        memory: args.contains("--memory"),
+                        compare: compare.then(|| threshold.unwrap_or(DEFAULT_THRESHOLD)),
+                        save_baseline: args.opt_value_from_str("--save-baseline")?,
+                        baseline: args.opt_value_from_str("--baseline")?,
+                        format: args.opt_value_from_str("--format")?,
+                        output: args.opt_value_from_str("--output")?,
+                        html: args.opt_value_from_str("--html")?,
+                        readme: args.contains("--readme"),
+                    };
 
-                AppArguments::Time {
-                    all,
-                    day: args.opt_free_from_str()?,
+                    AppArguments::Time {
+                        all,
+                        day: args.opt_free_from_str()?,
+                        options,
+                    }
                 }
             }
             Some("download") => AppArguments::Download {
@@ -62,6 +109,9 @@ mod args {
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
             },
+            Some("readme") => AppArguments::Readme {
+                solved_at: args.contains("--solved-at"),
+            },
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
@@ -70,8 +120,24 @@ mod args {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
+                record: args.contains("--record"),
                 time: args.contains("--time"),
                 dhat: args.contains("--dhat"),
+                export_graph: args.opt_value_from_str("--export-graph")?,
+            },
+            Some("status") => AppArguments::Status {
+                json: args.contains("--json"),
+                tests: !args.contains("--no-tests"),
+            },
+            Some("visualize") => AppArguments::Visualize {
+                day: args.free_from_str()?,
+                fps: args.opt_value_from_str("--fps")?,
+                gif: args.opt_value_from_str("--gif")?,
+                scale: args.opt_value_from_str("--scale")?,
+            },
+            Some("watch") => AppArguments::Watch {
+                day: args.free_from_str()?,
+                release: args.contains("--release"),
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -102,9 +168,12 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Time { day, all } => time::handle(day, all),
+            AppArguments::Time { day, all, options } => time::handle(day, all, &options),
+            AppArguments::ListBaselines => time::list_baselines(),
+            AppArguments::DeleteBaseline { name } => time::delete_baseline(&name),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
+            AppArguments::Readme { solved_at } => readme::handle(solved_at),
             AppArguments::Scaffold { day, download } => {
                 scaffold::handle(day);
                 if download {
@@ -117,7 +186,17 @@ fn main() {
                 time,
                 dhat,
                 submit,
-            } => solve::handle(day, release, time, dhat, submit),
+                record,
+                export_graph,
+            } => solve::handle(day, release, time, dhat, submit, record, export_graph),
+            AppArguments::Status { json, tests } => status::handle(json, tests),
+            AppArguments::Visualize {
+                day,
+                fps,
+                gif,
+                scale,
+            } => visualize::handle(day, fps, gif, scale),
+            AppArguments::Watch { day, release } => watch::handle(day, release),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
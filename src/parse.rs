@@ -0,0 +1,251 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Error raised by a day's parser, pointing at the offending part of the puzzle input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, in characters.
+    pub column: usize,
+    /// The offending text.
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, message: impl Into<String>) -> Self {
+        Self { line, column, text: text.to_string(), message: message.into() }
+    }
+
+    /// `text` found at `line:column` where something else was expected.
+    pub fn unexpected(line: usize, column: usize, text: impl Display) -> Self {
+        let text = text.to_string();
+        let message = format!("unexpected '{}'", text.escape_debug());
+        Self { line, column, text, message }
+    }
+
+    /// Error on `token`, a slice of the input line `text`. The column is derived from the
+    /// position of `token` in `text`.
+    pub fn in_line(line: usize, text: &str, token: &str, message: impl Into<String>) -> Self {
+        Self::new(line, column_of(text, token), token, message)
+    }
+
+    /// `token`, a slice of the input line `text`, is not what was expected.
+    pub fn unexpected_in_line(line: usize, text: &str, token: &str) -> Self {
+        Self { column: column_of(text, token), ..Self::unexpected(line, 0, token) }
+    }
+
+    /// The input line `text` stopped before a field `expected` was found.
+    pub fn end_of_line(line: usize, text: &str, expected: &str) -> Self {
+        Self::new(line, text.chars().count() + 1, "", format!("expected {expected}, found end of line"))
+    }
+
+    /// Parses `token`, a slice of the input line `text`, ignoring surrounding whitespace.
+    pub fn parse<T: FromStr>(line: usize, text: &str, token: &str) -> Result<T, Self> {
+        let trimmed = token.trim();
+        trimmed.parse().map_err(|_| Self::unexpected_in_line(line, text, trimmed))
+    }
+}
+
+/// 1-based column of `token` in `text`, or 1 if `token` is not a slice of `text`.
+fn column_of(text: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+    text.get(..offset).map_or(1, |before| before.chars().count() + 1)
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "input error at {}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A line of puzzle input along with its 1-based number, so that parsing errors can point
+/// back at it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// The lines of `input`, numbered from 1.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(index, text)| Line { number: index + 1, text })
+}
+
+/// The lines of `input` grouped in blocks separated by blank lines. Empty blocks are skipped.
+pub fn blocks(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks = vec![Vec::new()];
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            if blocks.last().is_some_and(|block| !block.is_empty()) {
+                blocks.push(Vec::new());
+            }
+        } else if let Some(block) = blocks.last_mut() {
+            block.push(line);
+        }
+    }
+    blocks.retain(|block| !block.is_empty());
+    blocks
+}
+
+impl<'a> Line<'a> {
+    /// Error on `token`, a slice of this line.
+    pub fn error(&self, token: &str, message: impl Into<String>) -> ParseError {
+        ParseError::in_line(self.number, self.text, token, message)
+    }
+
+    /// `token`, a slice of this line, is not what was expected.
+    pub fn unexpected(&self, token: &str) -> ParseError {
+        ParseError::unexpected_in_line(self.number, self.text, token)
+    }
+
+    /// The line stopped before a field `expected` was found.
+    pub fn end_of_line(&self, expected: &str) -> ParseError {
+        ParseError::end_of_line(self.number, self.text, expected)
+    }
+
+    /// Parses `token`, a slice of this line, ignoring surrounding whitespace.
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T, ParseError> {
+        ParseError::parse(self.number, self.text, token)
+    }
+
+    /// Parses `token`, a slice of this line, as a number written in `radix`.
+    pub fn parse_radix(&self, token: &str, radix: u32) -> Result<usize, ParseError> {
+        let trimmed = token.trim();
+        usize::from_str_radix(trimmed, radix).map_err(|_| self.unexpected(trimmed))
+    }
+
+    /// Every integer of the line, in order. A `-` right before a number is read as its sign
+    /// unless it follows a digit, so `3-4` gives `3` and `4`.
+    pub fn integers<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        let bytes = self.text.as_bytes();
+        let mut integers = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            if !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+            let start = if i > 0 && bytes[i - 1] == b'-' && (i < 2 || !bytes[i - 2].is_ascii_digit()) { i - 1 } else { i };
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            integers.push(self.parse(&self.text[start..i])?);
+        }
+        Ok(integers)
+    }
+
+    /// Splits `key: a b c` into the key and the whitespace separated values.
+    pub fn key_values(&self) -> Result<(&'a str, Vec<&'a str>), ParseError> {
+        let (key, values) = self.text.split_once(':').ok_or_else(|| self.end_of_line("':'"))?;
+        Ok((key.trim(), values.split_whitespace().collect()))
+    }
+
+    /// Splits `a -> b, c` into the source and the comma separated targets.
+    pub fn arrow(&self) -> Result<(&'a str, Vec<&'a str>), ParseError> {
+        let (source, targets) = self.text.split_once("->").ok_or_else(|| self.end_of_line("'->'"))?;
+        Ok((source.trim(), targets.split(',').map(str::trim).collect()))
+    }
+
+    /// Splits the line on any of `separators` and parses the fields into `T`, a tuple or an
+    /// array. Fails on a field that doesn't parse and on missing or extra fields.
+    pub fn fields<T: FromFields>(&self, separators: &[char]) -> Result<T, ParseError> {
+        let mut fields = self.text.split(separators);
+        let value = T::from_fields(self, &mut fields)?;
+        match fields.next() {
+            Some(extra) => Err(self.error(extra, "unexpected extra field")),
+            None => Ok(value),
+        }
+    }
+}
+
+/// Values that [`Line::fields`] can build from a sequence of fields.
+pub trait FromFields: Sized {
+    fn from_fields<'a>(line: &Line<'a>, fields: &mut impl Iterator<Item = &'a str>) -> Result<Self, ParseError>;
+}
+
+fn next_field<'a, T: FromStr>(line: &Line<'a>, fields: &mut impl Iterator<Item = &'a str>) -> Result<T, ParseError> {
+    let field = fields.next().ok_or_else(|| line.end_of_line("another field"))?;
+    line.parse(field)
+}
+
+macro_rules! impl_from_fields {
+    ($($t:ident),+) => {
+        impl<$($t: FromStr),+> FromFields for ($($t,)+) {
+            fn from_fields<'a>(line: &Line<'a>, fields: &mut impl Iterator<Item = &'a str>) -> Result<Self, ParseError> {
+                Ok(($(next_field::<$t>(line, fields)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_fields!(A);
+impl_from_fields!(A, B);
+impl_from_fields!(A, B, C);
+impl_from_fields!(A, B, C, D);
+impl_from_fields!(A, B, C, D, E);
+impl_from_fields!(A, B, C, D, E, F);
+
+impl<T: FromStr, const N: usize> FromFields for [T; N] {
+    fn from_fields<'a>(line: &Line<'a>, fields: &mut impl Iterator<Item = &'a str>) -> Result<Self, ParseError> {
+        let values = (0..N).map(|_| next_field(line, fields)).collect::<Result<Vec<T>, _>>()?;
+        Ok(values.try_into().unwrap_or_else(|_| unreachable!("collected exactly N fields")))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = ParseError::unexpected(12, 4, '@');
+        assert_eq!(error.to_string(), "input error at 12:4: unexpected '@'");
+        assert_eq!(ParseError::unexpected(1, 5, '\r').to_string(), "input error at 1:5: unexpected '\\r'");
+    }
+
+    #[test]
+    fn test_column() {
+        let line = "1,2,x~4";
+        let token = line.split([',', '~']).nth(2).unwrap();
+        assert_eq!(ParseError::parse::<u32>(3, line, token), Err(ParseError::unexpected(3, 5, 'x')));
+        assert_eq!(ParseError::end_of_line(2, line, "a number").column, 8);
+    }
+
+    #[test]
+    fn test_integers() {
+        let line = Line { number: 1, text: "p=0,-4 v=3-4 x-12" };
+        assert_eq!(line.integers::<i32>(), Ok(vec![0, -4, 3, 4, -12]));
+        assert_eq!(Line { number: 2, text: "99999999999" }.integers::<u32>().unwrap_err().column, 1);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let blocks = blocks("#.\n.#\n\n\n##\n");
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1], vec![Line { number: 5, text: "##" }]);
+    }
+
+    #[test]
+    fn test_splitters() {
+        let line = Line { number: 1, text: "jqt: rhn xhk nvd" };
+        assert_eq!(line.key_values(), Ok(("jqt", vec!["rhn", "xhk", "nvd"])));
+        let line = Line { number: 1, text: "%a -> inv, con" };
+        assert_eq!(line.arrow(), Ok(("%a", vec!["inv", "con"])));
+        assert_eq!(line.key_values().unwrap_err().column, 15);
+    }
+
+    #[test]
+    fn test_fields() {
+        let line = Line { number: 4, text: "1,0,1~1,2,1" };
+        assert_eq!(line.fields::<[u8; 6]>(&[',', '~']), Ok([1, 0, 1, 1, 2, 1]));
+        assert_eq!(line.fields::<(u8, i32, String)>(&['~']).map_err(|e| e.column), Err(1));
+        let line = Line { number: 4, text: "19, 13 @ -2" };
+        assert_eq!(line.fields::<(i64, i64, i64)>(&[',', '@']), Ok((19, 13, -2)));
+        assert_eq!(line.fields::<(i64, i64)>(&[',', '@']).map_err(|e| e.column), Err(9));
+        assert_eq!(line.fields::<[i64; 4]>(&[',', '@']).map_err(|e| e.column), Err(12));
+    }
+}
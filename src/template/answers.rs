@@ -0,0 +1,170 @@
+/// Answers found by `cargo solve --record` on the puzzle inputs, kept in `data/answers.json` so
+/// that a refactoring that changes an answer gets noticed.
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+
+use tinyjson::JsonValue;
+
+use crate::template::config::Config;
+use crate::template::Day;
+
+/// Latest answer of each part of a day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayAnswers {
+    pub day: Day,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// Answers of every day with at least one, sorted by day.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub data: Vec<DayAnswers>,
+}
+
+impl Answers {
+    /// Rehydrate the answers from their JSON file. If not present, returns no answers.
+    pub fn read_from_file() -> Self {
+        let path = &Config::get().paths.answers;
+        let Ok(s) = fs::read_to_string(path) else {
+            return Answers::default();
+        };
+        Answers::try_from(s).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", path.display());
+            Answers::default()
+        })
+    }
+
+    pub fn store_file(&self) -> Result<(), std::io::Error> {
+        fs::write(&Config::get().paths.answers, JsonValue::from(self).stringify().unwrap_or_default())
+    }
+
+    pub fn get(&self, day: Day, part: u8) -> Option<&str> {
+        let answers = self.data.iter().find(|a| a.day == day)?;
+        if part == 1 { &answers.part_1 } else { &answers.part_2 }.as_deref()
+    }
+
+    /// Sets the answer of a part. Returns the previous answer if it was a different one.
+    pub fn record(&mut self, day: Day, part: u8, answer: &str) -> Option<String> {
+        let index = match self.data.binary_search_by_key(&day, |a| a.day) {
+            Ok(index) => index,
+            Err(index) => {
+                self.data.insert(index, DayAnswers { day, part_1: None, part_2: None });
+                index
+            }
+        };
+        let slot = if part == 1 { &mut self.data[index].part_1 } else { &mut self.data[index].part_2 };
+        slot.replace(answer.to_string()).filter(|previous| previous != answer)
+    }
+}
+
+/// Records the answer of a part, and prints a notice if it differs from the one recorded before.
+pub fn record(day: Day, part: u8, answer: &str) {
+    let mut answers = Answers::read_from_file();
+    if answers.get(day, part) == Some(answer) {
+        return;
+    }
+    if let Some(previous) = answers.record(day, part, answer) {
+        println!("Part {part} answer changed, was {previous}.");
+    }
+    if let Err(e) = answers.store_file() {
+        eprintln!("Failed to record the answer: {e}");
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Answers> for JsonValue {
+    fn from(value: &Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(DayAnswers::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&DayAnswers> for JsonValue {
+    fn from(value: &DayAnswers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part_1".into(), value.part_1.clone().map_or(JsonValue::Null, JsonValue::String));
+        map.insert("part_2".into(), value.part_2.clone().map_or(JsonValue::Null, JsonValue::String));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for DayAnswers {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answers to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| day.parse().ok())
+            .ok_or("Expected answers.day to be a valid day.")?;
+
+        let part = |key: &str| json.get(key).and_then(|v| v.get::<String>()).cloned();
+
+        Ok(DayAnswers { day, part_1: part("part_1"), part_2: part("part_2") })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    #[test]
+    fn records_answers() {
+        let mut answers = Answers::default();
+        assert_eq!(answers.record(day!(15), 2, "145"), None);
+        assert_eq!(answers.record(day!(11), 1, "374"), None);
+        assert_eq!(answers.record(day!(15), 2, "145"), None);
+        assert_eq!(answers.record(day!(15), 2, "146"), Some("145".into()));
+        assert_eq!(answers.get(day!(15), 2), Some("146"));
+        assert_eq!(answers.get(day!(15), 1), None);
+        assert_eq!(answers.data.iter().map(|a| a.day).collect::<Vec<_>>(), [day!(11), day!(15)]);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let mut answers = Answers::default();
+        answers.record(day!(15), 1, "1320");
+        let json = JsonValue::from(&answers).stringify().unwrap();
+        assert_eq!(Answers::try_from(json), Ok(answers));
+    }
+}
@@ -4,6 +4,7 @@ use std::{
     process::{Command, Output, Stdio},
 };
 
+use crate::template::config::Config;
 use crate::template::Day;
 
 #[derive(Debug)]
@@ -81,18 +82,15 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
 }
 
 fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+    Config::get().paths.inputs.join(format!("{day}.txt")).display().to_string()
 }
 
 fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+    Config::get().paths.puzzles.join(format!("{day}.md")).display().to_string()
 }
 
 fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
+    Config::get().year
 }
 
 fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
@@ -0,0 +1,13 @@
+use std::process;
+
+use crate::template::readme_stars;
+
+pub fn handle(solved_at: bool) {
+    match readme_stars::update(solved_at) {
+        Ok(stars) => println!("Updated the readme with {} star(s) over {} day(s).", stars.count(), stars.data.len()),
+        Err(e) => {
+            eprintln!("Failed to update the readme stars: {e}");
+            process::exit(1);
+        }
+    }
+}
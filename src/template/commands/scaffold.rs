@@ -1,9 +1,10 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
     process,
 };
 
+use crate::template::config::Config;
 use crate::template::Day;
 
 const MODULE_TEMPLATE: &str =
@@ -14,14 +15,27 @@ fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
 pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
+    let config = Config::get();
+    let input_path = config.paths.inputs.join(format!("{day}.txt")).display().to_string();
+    let example_path = config.paths.examples.join(format!("{day}.txt")).display().to_string();
     let module_path = format!("src/bin/{day}.rs");
 
+    let template = match &config.template {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("Failed to read template {}: {e}", path.display());
+            process::exit(1);
+        }),
+        None => MODULE_TEMPLATE.to_string(),
+    };
+
     let mut file = match safe_create_file(&module_path) {
         Ok(file) => file,
         Err(e) => {
@@ -31,7 +45,7 @@ pub fn handle(day: Day) {
     };
 
     match file.write_all(
-        MODULE_TEMPLATE
+        template
             .replace("%DAY_NUMBER%", &day.into_inner().to_string())
             .as_bytes(),
     ) {
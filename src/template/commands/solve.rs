@@ -2,7 +2,15 @@ use std::process::{Command, Stdio};
 
 use crate::template::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(
+    day: Day,
+    release: bool,
+    time: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    record: bool,
+    export_graph: Option<String>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -27,6 +35,15 @@ pub fn handle(day: Day, release: bool, time: bool, dhat: bool, submit_part: Opti
         cmd_args.push("--time".to_string());
     }
 
+    if record {
+        cmd_args.push("--record".to_string());
+    }
+
+    if let Some(path) = export_graph {
+        cmd_args.push("--export-graph".to_string());
+        cmd_args.push(path);
+    }
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -0,0 +1,24 @@
+use crate::template::config::Config;
+use crate::template::status;
+use crate::template::watch::run_tests;
+
+pub fn handle(json: bool, tests: bool) {
+    let mut statuses = status::collect();
+    if tests {
+        for status in statuses.iter_mut().filter(|s| s.scaffolded) {
+            if !json {
+                eprintln!("Testing day {}...", status.day);
+            }
+            status.tests = Some(run_tests(status.day, false));
+        }
+    }
+
+    if json {
+        match status::to_json(Config::get().year, &statuses).stringify() {
+            Ok(json) => println!("{json}"),
+            Err(e) => eprintln!("Failed to write the status: {e}"),
+        }
+    } else {
+        println!("{}", status::table(&statuses));
+    }
+}
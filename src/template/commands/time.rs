@@ -1,16 +1,60 @@
 use std::collections::HashSet;
+use std::{fs, process};
 
+use crate::template::config::Config;
+use crate::template::export::{export, Format};
+use crate::template::history::{compare, print_comparisons, History, HistoryEntry, RunInfo};
+use crate::template::html_report;
 use crate::template::run_multi::run_multi;
-use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::timings::{format_comparison_table, Baseline, Timings};
+use crate::template::{all_days, readme_benchmarks, Day, ANSI_BOLD, ANSI_RESET};
+
+/// Flags of the `time` command.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Also record the heap usage of each part.
+    pub memory: bool,
+    /// Flag parts that got slower than in the previous run, or the baseline, by more than this percentage.
+    pub compare: Option<f64>,
+    /// Save the timings under this name.
+    pub save_baseline: Option<String>,
+    /// Compare the timings with the baseline saved under this name.
+    pub baseline: Option<String>,
+    /// Write the timings in this format instead of updating the README.
+    pub format: Option<Format>,
+    /// File to write the formatted timings to, defaults to stdout.
+    pub output: Option<String>,
+    /// Write a standalone HTML report with charts of the timings to this file.
+    pub html: Option<String>,
+    /// Update the README even when saving, comparing with a baseline or exporting the timings.
+    pub readme: bool,
+}
+
+pub fn handle(day: Option<Day>, recreate_all: bool, options: &Options) {
+    let format = options.format.or_else(|| options.output.as_deref().and_then(Format::from_path));
+    if options.output.is_some() && format.is_none() {
+        eprintln!("Could not guess the format from the output file name, please pass `--format csv|json|markdown`.");
+        process::exit(1);
+    }
 
-pub fn handle(day: Option<Day>, recreate_all: bool) {
     let stored_timings = Timings::read_from_file();
+    let history = History::read_from_file();
+
+    // load the config and baseline first so that a typo doesn't cost a full benchmark run.
+    let table = &Config::get().benchmarks;
+    let baseline = options.baseline.as_deref().map(|name| {
+        Baseline::load(name).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            process::exit(1);
+        })
+    });
 
     let days_to_run = day.map_or_else(
         || {
-            if recreate_all {
+            if recreate_all || options.save_baseline.is_some() {
                 all_days().collect()
+            } else if let Some(baseline) = &baseline {
+                baseline.timings.data.iter().map(|t| t.day).collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
                 all_days()
@@ -21,18 +65,121 @@ pub fn handle(day: Option<Day>, recreate_all: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, true, true, options.memory).unwrap();
+
+    let run = RunInfo::current();
+    let entries = HistoryEntry::from_timings(&timings, &run);
+    if let Err(e) = History::append(&entries) {
+        eprintln!("Failed to store benchmark history: {e}");
+    }
 
     let merged_timings = stored_timings.merge(&timings);
-    merged_timings.store_file().unwrap();
 
-    println!();
-    match readme_benchmarks::update(merged_timings) {
-        Ok(()) => {
-            println!("Stored updated benchmarks.");
+    if let Some(format) = format {
+        let exported = export(&merged_timings, format, table);
+        match &options.output {
+            Some(path) => match fs::write(path, exported) {
+                Ok(()) => println!("\nWrote timings of {} day(s) to {path}.", merged_timings.data.len()),
+                Err(e) => eprintln!("Failed to write {path}: {e}"),
+            },
+            None => print!("\n{exported}"),
+        }
+    }
+
+    if let Some(path) = &options.html {
+        let mut history = history.clone();
+        history.entries.extend(entries.iter().cloned());
+        match fs::write(path, html_report::report(&merged_timings, &history, &run, table.total_unit)) {
+            Ok(()) => println!("\nWrote the benchmark report to {path}."),
+            Err(e) => eprintln!("Failed to write {path}: {e}"),
         }
-        Err(_) => {
-            eprintln!("Failed to store updated benchmarks.");
+    }
+
+    if let Err(e) = merged_timings.store_file() {
+        eprintln!("Failed to store timings: {e}");
+    }
+
+    // working with baselines, exports or reports leaves the README alone unless asked to.
+    if options.readme || (options.save_baseline.is_none() && baseline.is_none() && format.is_none() && options.html.is_none()) {
+        println!();
+        match readme_benchmarks::update(merged_timings, table) {
+            Ok(()) => {
+                println!("Stored updated benchmarks.");
+            }
+            Err(e) => {
+                eprintln!("Failed to store updated benchmarks: {e}");
+            }
+        }
+    }
+
+    if let Some(name) = &options.save_baseline {
+        let days = timings.data.len();
+        let saved = Baseline { name: name.clone(), run, timings: timings.clone() };
+        println!();
+        match saved.save() {
+            Ok(()) => println!("Saved baseline `{name}` with {days} day(s)."),
+            Err(e) => eprintln!("Failed to save baseline `{name}`: {e}"),
+        }
+    }
+
+    let regressions = if let Some(baseline) = &baseline {
+        let comparisons = baseline.compare(&timings);
+        println!();
+        println!(
+            "{ANSI_BOLD}Compared to baseline `{}`{ANSI_RESET} ({}{})",
+            baseline.name,
+            baseline.run.date(),
+            baseline.run.commit.as_deref().map_or(String::new(), |c| format!(", commit {}", &c[..c.len().min(7)]))
+        );
+        if comparisons.is_empty() {
+            println!("No day in common with the baseline.");
+        } else {
+            println!("{}", format_comparison_table(&comparisons));
+        }
+        options.compare.map_or(0, |threshold| {
+            comparisons.iter().filter(|c| c.is_regression(threshold)).count()
+        })
+    } else if let Some(threshold) = options.compare {
+        println!();
+        print_comparisons(&compare(&history.latest(), &entries), threshold)
+    } else {
+        0
+    };
+
+    if regressions > 0 {
+        eprintln!("{regressions} part(s) regressed by more than {}%.", options.compare.unwrap_or_default());
+        process::exit(1);
+    }
+}
+
+pub fn list_baselines() {
+    let baselines = Baseline::list().unwrap_or_else(|e| {
+        eprintln!("Failed to read baselines: {e}");
+        process::exit(1);
+    });
+
+    if baselines.is_empty() {
+        println!("No baselines saved yet, create one with `cargo time --save-baseline <name>`.");
+    }
+
+    for baseline in baselines {
+        let mut details = vec![baseline.run.date(), format!("{} day(s)", baseline.timings.data.len())];
+        if let Some(commit) = &baseline.run.commit {
+            details.push(format!("commit {}", &commit[..commit.len().min(7)]));
+        }
+        if let Some(host) = &baseline.run.host {
+            details.push(format!("on {host}"));
+        }
+        println!("{ANSI_BOLD}{}{ANSI_RESET} ({})", baseline.name, details.join(", "));
+    }
+}
+
+pub fn delete_baseline(name: &str) {
+    match Baseline::delete(name) {
+        Ok(()) => println!("Deleted baseline `{name}`."),
+        Err(e) => {
+            eprintln!("Failed to delete baseline `{name}`: {e}");
+            process::exit(1);
         }
     }
 }
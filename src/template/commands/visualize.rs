@@ -0,0 +1,37 @@
+use std::process::{Command, Stdio};
+
+use crate::template::Day;
+
+pub fn handle(day: Day, fps: Option<u32>, gif: Option<String>, scale: Option<u32>) {
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--release".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+        "--".to_string(),
+        "--visualize".to_string(),
+    ];
+
+    if let Some(fps) = fps {
+        cmd_args.extend(["--fps".to_string(), fps.to_string()]);
+    }
+
+    if let Some(gif) = gif {
+        cmd_args.extend(["--gif".to_string(), gif]);
+    }
+
+    if let Some(scale) = scale {
+        cmd_args.extend(["--scale".to_string(), scale.to_string()]);
+    }
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    cmd.wait().unwrap();
+}
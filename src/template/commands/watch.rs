@@ -0,0 +1,45 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{process, thread};
+
+use crate::template::watch::{self, Debounce, Watcher};
+use crate::template::{Day, ANSI_ITALIC, ANSI_RESET};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Quiet time after the last change before running, so that editors saving several files trigger one run.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(400);
+
+pub fn handle(day: Day, release: bool) {
+    let paths = watch::watched_paths(day);
+    if !Path::new(&paths[0]).exists() {
+        eprintln!("day {day} has no solution yet, run `cargo scaffold {}` first.", day.into_inner());
+        process::exit(1);
+    }
+
+    let mut watcher = Watcher::new(paths);
+    let mut debounce = Debounce::new(DEBOUNCE_DELAY);
+    let mut changed: Vec<PathBuf> = vec![];
+    let mut runs = 1;
+    show(watch::run(day, release, runs, vec![]));
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let polled = watcher.poll();
+        let is_changed = !polled.is_empty();
+        for path in polled {
+            if !changed.contains(&path) {
+                changed.push(path);
+            }
+        }
+        if debounce.update(is_changed, Instant::now()) {
+            runs += 1;
+            show(watch::run(day, release, runs, std::mem::take(&mut changed)));
+        }
+    }
+}
+
+fn show(report: watch::Report) {
+    // clear the terminal so that the panel of the latest run is the only one on screen.
+    print!("\x1b[2J\x1b[H{report}");
+    println!("\n{ANSI_ITALIC}Watching for changes, press Ctrl+C to stop.{ANSI_RESET}");
+}
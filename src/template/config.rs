@@ -0,0 +1,642 @@
+/// Settings read from `aoc.toml` at the crate root. A missing file or key keeps the default,
+/// which matches how the template behaves without the file. Every key can be overridden by an
+/// environment variable named after its path, e.g. `AOC_YEAR` or `AOC_PATHS_INPUTS`.
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::{env, fs, io, process};
+
+static CONFIG_FILE_PATH: &str = "aoc.toml";
+
+/// A value of the subset of TOML understood here: strings, numbers, booleans and single-line arrays.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+/// Value of an environment variable, `None` if it isn't set.
+pub type EnvLookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Keys of a TOML document, flattened to their dotted path, e.g. `benchmarks.readme`.
+#[derive(Default)]
+pub struct Document<'a> {
+    values: HashMap<String, Value>,
+    /// Looks up the environment variables overriding the keys, if they are overridden.
+    env: Option<EnvLookup<'a>>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Parser { line: usize, message: String },
+    Value { key: String, message: String },
+    Env { variable: String, message: String },
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parser { line, message } => write!(f, "{CONFIG_FILE_PATH}:{line}: {message}"),
+            Error::Value { key, message } => write!(f, "{CONFIG_FILE_PATH}: `{key}` {message}"),
+            Error::Env { variable, message } => write!(f, "environment variable `{variable}` {message}"),
+            Error::IO(e) => write!(f, "{CONFIG_FILE_PATH}: {e}"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl fmt::Debug for Document<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Document").field("values", &self.values).field("env", &self.env.is_some()).finish()
+    }
+}
+
+impl<'a> Document<'a> {
+    /// The same keys, overridden by the variables `env` returns, e.g. `|variable| env::var(variable).ok()`.
+    pub fn with_env(self, env: EnvLookup<'a>) -> Self {
+        Document { env: Some(env), ..self }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// The value of `key` converted with `T::try_from`, `None` if the key is absent. An environment
+    /// variable is read as a TOML value, or as a string if it isn't one, e.g. `AOC_PATHS_DATA=/tmp/aoc`.
+    pub fn get_as<T: for<'v> TryFrom<&'v Value, Error = String>>(&self, key: &str) -> Result<Option<T>, Error> {
+        let variable = env_variable(key);
+        let from_env = self
+            .env
+            .and_then(|env| env(&variable))
+            .map(|s| parse_value(&s).unwrap_or(Value::String(s)));
+        match &from_env {
+            Some(value) => T::try_from(value).map(Some).map_err(|message| Error::Env { variable, message }),
+            None => self
+                .get(key)
+                .map(|value| T::try_from(value).map_err(|message| Error::Value { key: key.into(), message }))
+                .transpose(),
+        }
+    }
+}
+
+impl FromStr for Document<'_> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = HashMap::new();
+        let mut table = String::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let error = |message: &str| Error::Parser { line: index + 1, message: message.into() };
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name.strip_suffix(']').ok_or_else(|| error("expected `]` after the table name"))?;
+                table = format!("{}.", name.trim());
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| error("expected `key = value`"))?;
+            let key = format!("{table}{}", key.trim().trim_matches('"'));
+            let value = parse_value(value.trim()).map_err(|message| error(&message))?;
+            if values.insert(key.clone(), value).is_some() {
+                return Err(error(&format!("`{key}` is defined twice")));
+            }
+        }
+
+        Ok(Document { values, env: None })
+    }
+}
+
+/// Name of the environment variable overriding `key`, e.g. `AOC_BENCHMARKS_README`.
+fn env_variable(key: &str) -> String {
+    format!("AOC_{}", key.replace('.', "_").to_uppercase())
+}
+
+/// The line without its `#` comment, ignoring `#` inside strings.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(s: &str) -> Result<Value, String> {
+    if let Some(inner) = s.strip_prefix('[') {
+        let inner = inner.strip_suffix(']').ok_or("arrays must fit on one line")?;
+        return split_array(inner)
+            .into_iter()
+            .map(|item| parse_value(item.trim()))
+            .collect::<Result<_, _>>()
+            .map(Value::Array);
+    }
+    if let Some(inner) = s.strip_prefix('"') {
+        let inner = inner.strip_suffix('"').ok_or("unterminated string")?;
+        return Ok(Value::String(inner.replace("\\\"", "\"").replace("\\\\", "\\")));
+    }
+    if let Some(inner) = s.strip_prefix('\'') {
+        let inner = inner.strip_suffix('\'').ok_or("unterminated string")?;
+        return Ok(Value::String(inner.into()));
+    }
+    match s {
+        "true" => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        _ => {}
+    }
+    let number = s.replace('_', "");
+    if let Ok(integer) = number.parse() {
+        return Ok(Value::Integer(integer));
+    }
+    number.parse().map(Value::Float).map_err(|_| format!("unsupported value `{s}`"))
+}
+
+/// Items of an array, split on the commas that are not inside strings.
+fn split_array(s: &str) -> Vec<&str> {
+    let mut items = vec![];
+    let (mut start, mut quote) = (0, None);
+    for (index, c) in s.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (',', None) => {
+                items.push(&s[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&s[start..]);
+    items.into_iter().filter(|item| !item.trim().is_empty()).collect()
+}
+
+impl TryFrom<&Value> for String {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            _ => Err("should be a string.".into()),
+        }
+    }
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl TryFrom<&Value> for $t {
+            type Error = String;
+
+            fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                match value {
+                    Value::Integer(i) => <$t>::try_from(*i).map_err(|_| format!("should be between {} and {}.", <$t>::MIN, <$t>::MAX)),
+                    _ => Err("should be an integer.".into()),
+                }
+            }
+        })*
+    };
+}
+
+impl_integer!(u16, u64, usize);
+
+impl TryFrom<&Value> for PathBuf {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        String::try_from(value).map(PathBuf::from)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Settings of the whole template, see the "Configuration" section of the README for the keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Year of the puzzles, `None` to let aoc-cli pick the current one.
+    pub year: Option<u16>,
+    pub paths: Paths,
+    pub budget: Budget,
+    pub benchmarks: BenchmarkTable,
+    /// File holding the stars table written by `cargo readme`.
+    pub stars_readme: String,
+    /// Template of `cargo scaffold`, `None` for the one built into the binary.
+    pub template: Option<PathBuf>,
+}
+
+/// Where puzzle data and the files written by the template live, relative to the crate root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Paths {
+    /// Holds the timings, their history, baselines and stars.
+    pub data: PathBuf,
+    pub inputs: PathBuf,
+    pub examples: PathBuf,
+    pub puzzles: PathBuf,
+    /// Answers found by `cargo solve` on the inputs.
+    pub answers: PathBuf,
+}
+
+/// How long `--time` benches each part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Budget {
+    pub time: Duration,
+    pub min_samples: u128,
+    pub max_samples: u128,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            year: None,
+            paths: Paths::in_dir(PathBuf::from("data")),
+            budget: Budget::default(),
+            benchmarks: BenchmarkTable::default(),
+            stars_readme: "README.md".into(),
+            template: None,
+        }
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget { time: Duration::from_secs(1), min_samples: 10, max_samples: 10_000 }
+    }
+}
+
+impl Config {
+    /// The config of this run, read once. Exits if `aoc.toml` or an override is invalid.
+    pub fn get() -> &'static Config {
+        static CONFIG: OnceLock<Config> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            Config::read_from_file().unwrap_or_else(|e| {
+                eprintln!("{e}");
+                process::exit(1);
+            })
+        })
+    }
+
+    /// Reads `aoc.toml`, overridden by the environment. If not present, only the environment is used.
+    fn read_from_file() -> Result<Self, Error> {
+        let toml = match fs::read_to_string(CONFIG_FILE_PATH) {
+            Ok(s) => s,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Config::from_sources(&toml, |variable| env::var(variable).ok())
+    }
+
+    /// The config of the TOML document `toml`, overridden by the variables `env` returns.
+    pub fn from_sources(toml: &str, env: impl Fn(&str) -> Option<String>) -> Result<Self, Error> {
+        let document: Document = toml.parse()?;
+        Config::from_document(&document.with_env(&env))
+    }
+
+    pub fn from_document(document: &Document) -> Result<Self, Error> {
+        let default = Config::default();
+        let data: PathBuf = document.get_as("paths.data")?.unwrap_or(default.paths.data);
+        let paths = Paths::in_dir(data);
+        Ok(Config {
+            year: document.get_as("year")?,
+            paths: Paths {
+                inputs: document.get_as("paths.inputs")?.unwrap_or(paths.inputs),
+                examples: document.get_as("paths.examples")?.unwrap_or(paths.examples),
+                puzzles: document.get_as("paths.puzzles")?.unwrap_or(paths.puzzles),
+                answers: document.get_as("paths.answers")?.unwrap_or(paths.answers),
+                data: paths.data,
+            },
+            budget: Budget::from_document(document)?,
+            benchmarks: BenchmarkTable::from_document(document)?,
+            stars_readme: document.get_as("stars.readme")?.unwrap_or(default.stars_readme),
+            template: document.get_as("scaffold.template")?,
+        })
+    }
+}
+
+impl Paths {
+    /// The default layout under `data`.
+    fn in_dir(data: PathBuf) -> Self {
+        Paths {
+            inputs: data.join("inputs"),
+            examples: data.join("examples"),
+            puzzles: data.join("puzzles"),
+            answers: data.join("answers.json"),
+            data,
+        }
+    }
+
+    /// Directory of a folder passed to `read_file`: `inputs`, `examples`, or any other under `data`.
+    pub fn folder(&self, folder: &str) -> PathBuf {
+        match folder {
+            "inputs" => self.inputs.clone(),
+            "examples" => self.examples.clone(),
+            folder => self.data.join(folder),
+        }
+    }
+
+    pub fn timings(&self) -> PathBuf {
+        self.data.join("timings.json")
+    }
+
+    pub fn history(&self) -> PathBuf {
+        self.data.join("timings-history.jsonl")
+    }
+
+    pub fn baselines(&self) -> PathBuf {
+        self.data.join("baselines")
+    }
+
+    pub fn stars(&self) -> PathBuf {
+        self.data.join("stars.json")
+    }
+}
+
+impl Budget {
+    fn from_document(document: &Document) -> Result<Self, Error> {
+        let default = Budget::default();
+        let time = document.get_as::<u64>("benchmarks.budget_ms")?.map_or(default.time, Duration::from_millis);
+        let min_samples = document.get_as::<u64>("benchmarks.min_samples")?.map_or(default.min_samples, u128::from);
+        let max_samples = document.get_as::<u64>("benchmarks.max_samples")?.map_or(default.max_samples, u128::from);
+        if min_samples == 0 || min_samples > max_samples {
+            return Err(Error::Value {
+                key: "benchmarks.min_samples".into(),
+                message: "should be at least 1 and at most `benchmarks.max_samples`.".into(),
+            });
+        }
+        Ok(Budget { time, min_samples, max_samples })
+    }
+
+    /// Number of samples to take of a function that ran once in `base_time`.
+    pub fn samples(&self, base_time: Duration) -> u128 {
+        (self.time.as_nanos() / base_time.as_nanos().max(10)).clamp(self.min_samples, self.max_samples)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Layout of the benchmark table, from the `[benchmarks]` table of `aoc.toml`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchmarkTable {
+    /// File holding the table between the benchmark markers.
+    pub readme: String,
+    /// Number of `#` of the heading.
+    pub heading_level: usize,
+    /// Columns after part 2, each shown once a day has the data.
+    pub columns: Vec<Column>,
+    pub sort: Sort,
+    /// Number of slowest days whose row is written in bold.
+    pub highlight_slowest: usize,
+    pub total_unit: Unit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    /// Benchmark samples of both parts.
+    Samples,
+    /// Peak heap usage of both parts, recorded with `time --memory`.
+    Memory,
+    /// Time to read the input.
+    ReadInput,
+    /// Time of the parse shared by both parts, see `solution!(DAY, parse = ...)`.
+    Parse,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sort {
+    Day,
+    /// Slowest day first.
+    Total,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Nanos,
+    Micros,
+    Millis,
+    Seconds,
+    /// The largest unit that keeps the total above 1.
+    Auto,
+}
+
+impl Default for BenchmarkTable {
+    fn default() -> Self {
+        BenchmarkTable {
+            readme: "README.md".into(),
+            heading_level: 2,
+            columns: vec![Column::Memory],
+            sort: Sort::Day,
+            highlight_slowest: 0,
+            total_unit: Unit::Millis,
+        }
+    }
+}
+
+impl BenchmarkTable {
+    pub fn from_document(document: &Document) -> Result<Self, Error> {
+        let default = Self::default();
+        let heading_level = document.get_as("benchmarks.heading_level")?.unwrap_or(default.heading_level);
+        if !(1..=6).contains(&heading_level) {
+            return Err(Error::Value { key: "benchmarks.heading_level".into(), message: "should be between 1 and 6.".into() });
+        }
+        Ok(BenchmarkTable {
+            readme: document.get_as("benchmarks.readme")?.unwrap_or(default.readme),
+            heading_level,
+            columns: document.get_as::<Columns>("benchmarks.columns")?.map_or(default.columns, |c| c.0),
+            sort: document.get_as("benchmarks.sort")?.unwrap_or(default.sort),
+            highlight_slowest: document.get_as("benchmarks.highlight_slowest")?.unwrap_or(default.highlight_slowest),
+            total_unit: document.get_as("benchmarks.total_unit")?.unwrap_or(default.total_unit),
+        })
+    }
+
+    pub fn heading(&self) -> String {
+        "#".repeat(self.heading_level)
+    }
+}
+
+impl Unit {
+    /// `nanos` in this unit, e.g. `190.00ms`.
+    #[must_use]
+    pub fn format(self, nanos: f64) -> String {
+        let unit = match self {
+            Unit::Auto => match nanos {
+                n if n >= 1e9 => Unit::Seconds,
+                n if n >= 1e6 => Unit::Millis,
+                n if n >= 1e3 => Unit::Micros,
+                _ => Unit::Nanos,
+            },
+            unit => unit,
+        };
+        match unit {
+            Unit::Nanos => format!("{nanos:.0}ns"),
+            Unit::Micros => format!("{:.2}µs", nanos / 1e3),
+            Unit::Seconds => format!("{:.2}s", nanos / 1e9),
+            _ => format!("{:.2}ms", nanos / 1e6),
+        }
+    }
+}
+
+/// Wrapper to read an array of column names.
+#[derive(Debug)]
+struct Columns(Vec<Column>);
+
+impl TryFrom<&Value> for Columns {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(items) => items.iter().map(Column::try_from).collect::<Result<_, _>>().map(Columns),
+            _ => Err("should be an array.".into()),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Column {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match String::try_from(value)?.as_str() {
+            "samples" => Ok(Column::Samples),
+            "memory" => Ok(Column::Memory),
+            "read_input" => Ok(Column::ReadInput),
+            "parse" => Ok(Column::Parse),
+            other => Err(format!("has unknown column `{other}`, expected samples, memory, read_input or parse.")),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Sort {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match String::try_from(value)?.as_str() {
+            "day" => Ok(Sort::Day),
+            "total" => Ok(Sort::Total),
+            other => Err(format!("should be `day` or `total`, not `{other}`.")),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Unit {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match String::try_from(value)?.as_str() {
+            "ns" => Ok(Unit::Nanos),
+            "us" | "µs" => Ok(Unit::Micros),
+            "ms" => Ok(Unit::Millis),
+            "s" => Ok(Unit::Seconds),
+            "auto" => Ok(Unit::Auto),
+            other => Err(format!("should be ns, µs, ms, s or auto, not `{other}`.")),
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_document() {
+        let document: Document = "# benchmarks\n[benchmarks]\nreadme = \"docs/#bench.md\" # comment\ncolumns = [\"samples\", 'read_input',]\nhighlight_slowest = 1_0\nratio = 2.5\nenabled = true"
+            .parse()
+            .unwrap();
+        assert_eq!(document.get("benchmarks.readme"), Some(&Value::String("docs/#bench.md".into())));
+        assert_eq!(
+            document.get("benchmarks.columns"),
+            Some(&Value::Array(vec![Value::String("samples".into()), Value::String("read_input".into())]))
+        );
+        assert_eq!(document.get("benchmarks.highlight_slowest"), Some(&Value::Integer(10)));
+        assert_eq!(document.get("benchmarks.ratio"), Some(&Value::Float(2.5)));
+        assert_eq!(document.get("benchmarks.enabled"), Some(&Value::Boolean(true)));
+    }
+
+    #[test]
+    fn reports_line_of_errors() {
+        let error = "[benchmarks]\n\nreadme".parse::<Document>().unwrap_err();
+        assert_eq!(error.to_string(), "aoc.toml:3: expected `key = value`");
+        assert!("a = [1,\n2]".parse::<Document>().is_err());
+        assert!("a = 1\na = 2".parse::<Document>().is_err());
+    }
+
+    #[test]
+    fn reads_benchmark_table() {
+        assert_eq!(BenchmarkTable::from_document(&Document::default()).unwrap(), BenchmarkTable::default());
+
+        let document = "[benchmarks]\nheading_level = 3\ncolumns = [\"samples\"]\nsort = \"total\"\ntotal_unit = \"s\"".parse().unwrap();
+        let table = BenchmarkTable::from_document(&document).unwrap();
+        assert_eq!(table.heading(), "###");
+        assert_eq!(table.columns, [Column::Samples]);
+        assert_eq!((table.sort, table.total_unit), (Sort::Total, Unit::Seconds));
+
+        let document = "[benchmarks]\ncolumns = [\"speed\"]".parse().unwrap();
+        assert!(BenchmarkTable::from_document(&document).unwrap_err().to_string().contains("unknown column `speed`"));
+    }
+
+    #[test]
+    fn formats_total() {
+        assert_eq!(Unit::Millis.format(190e6), "190.00ms");
+        assert_eq!(Unit::Seconds.format(190e6), "0.19s");
+        assert_eq!(Unit::Auto.format(1.5e3), "1.50µs");
+        assert_eq!(Unit::Auto.format(12.0), "12ns");
+    }
+
+    #[test]
+    fn reads_config() {
+        assert_eq!(Config::from_document(&Document::default()).unwrap(), Config::default());
+        assert_eq!(Config::default().paths.folder("inputs"), PathBuf::from("data/inputs"));
+
+        let document = "year = 2022\n[paths]\ndata = \"aoc\"\nexamples = \"tests/examples\"\n[benchmarks]\nbudget_ms = 250\n[scaffold]\ntemplate = \"day.rs.txt\""
+            .parse()
+            .unwrap();
+        let config = Config::from_document(&document).unwrap();
+        assert_eq!(config.year, Some(2022));
+        assert_eq!(config.paths.folder("inputs"), PathBuf::from("aoc/inputs"));
+        assert_eq!(config.paths.folder("examples"), PathBuf::from("tests/examples"));
+        assert_eq!(config.paths.timings(), PathBuf::from("aoc/timings.json"));
+        assert_eq!(config.budget.samples(Duration::from_millis(1)), 250);
+        assert_eq!(config.budget.samples(Duration::from_secs(1)), 10);
+        assert_eq!(config.template, Some(PathBuf::from("day.rs.txt")));
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let document = "year = 100000".parse().unwrap();
+        assert_eq!(Config::from_document(&document).unwrap_err().to_string(), "aoc.toml: `year` should be between 0 and 65535.");
+        let document = "[benchmarks]\nmin_samples = 100\nmax_samples = 10".parse().unwrap();
+        assert!(Config::from_document(&document).is_err());
+    }
+
+    #[test]
+    fn overrides_from_environment() {
+        let toml = "[tests]\noverride = \"file\"\nkept = 1";
+        let variables = HashMap::from([("AOC_TESTS_OVERRIDE", "[\"env\"]"), ("AOC_TESTS_KEPT", "2")]);
+        let env = |variable: &str| variables.get(variable).map(|value| value.to_string());
+        let document: Document = toml.parse().unwrap();
+        assert_eq!(document.get_as::<usize>("tests.kept").unwrap(), Some(1));
+
+        let document = document.with_env(&env);
+        assert_eq!(document.get_as::<Columns>("tests.override").unwrap_err().to_string(), "environment variable `AOC_TESTS_OVERRIDE` has unknown column `env`, expected samples, memory, read_input or parse.");
+        assert_eq!(document.get_as::<usize>("tests.kept").unwrap(), Some(2));
+        let env = |variable: &str| (variable == "AOC_TESTS_OVERRIDE").then(|| "data/inputs".to_string());
+        let document: Document = toml.parse().unwrap();
+        assert_eq!(document.with_env(&env).get_as::<PathBuf>("tests.override").unwrap(), Some(PathBuf::from("data/inputs")));
+
+        let config = Config::from_sources("year = 2022", |variable| (variable == "AOC_YEAR").then(|| "2023".into())).unwrap();
+        assert_eq!(config.year, Some(2023));
+    }
+}
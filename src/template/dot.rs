@@ -0,0 +1,275 @@
+/// Graphviz DOT output for the days whose puzzle input is a graph, written with `cargo solve DAY --export-graph <path>`.
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::{env, fs, process};
+
+use crate::template::visualize::Color;
+
+/// Implemented by a day to describe its puzzle input as a graph, enabled with
+/// `solution!(DAY, graph = Type)`. Solutions never write the graph unless asked to.
+pub trait ExportGraph {
+    fn graph(input: &str) -> Result<DotGraph, String>;
+}
+
+/// A graph in the DOT language. Nodes and edges are written in the order they were added,
+/// nodes of a cluster inside its `subgraph` block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DotGraph {
+    name: String,
+    directed: bool,
+    attributes: Vec<(String, String)>,
+    clusters: Vec<Cluster>,
+    nodes: Vec<Node>,
+    /// Index in `nodes` of each node id.
+    node_indices: HashMap<String, usize>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    id: String,
+    label: Option<String>,
+    color: Option<Color>,
+    shape: Option<String>,
+    cluster: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edge {
+    from: String,
+    to: String,
+    label: Option<String>,
+    color: Option<Color>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cluster {
+    id: String,
+    label: String,
+    color: Option<Color>,
+}
+
+impl DotGraph {
+    /// A `digraph`, whose edges are drawn as arrows.
+    pub fn directed(name: &str) -> Self {
+        Self { name: name.to_string(), directed: true, ..Self::default() }
+    }
+
+    /// A `graph`, whose edges have no direction.
+    pub fn undirected(name: &str) -> Self {
+        Self { name: name.to_string(), directed: false, ..Self::default() }
+    }
+
+    /// Sets a graph attribute, e.g. `("layout", "neato")` or `("rankdir", "LR")`.
+    pub fn attribute(&mut self, key: &str, value: &str) -> &mut Self {
+        self.attributes.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a node, or returns the existing one with the same id so that it can be decorated.
+    pub fn node(&mut self, id: impl Display) -> &mut Node {
+        let id = id.to_string();
+        let index = match self.node_indices.get(&id) {
+            Some(&index) => index,
+            None => {
+                self.node_indices.insert(id.clone(), self.nodes.len());
+                self.nodes.push(Node { id, label: None, color: None, shape: None, cluster: None });
+                self.nodes.len() - 1
+            }
+        };
+        &mut self.nodes[index]
+    }
+
+    /// Adds an edge, and its ends as nodes if they were not added yet.
+    pub fn edge(&mut self, from: impl Display, to: impl Display) -> &mut Edge {
+        let (from, to) = (from.to_string(), to.to_string());
+        self.node(&from);
+        self.node(&to);
+        self.edges.push(Edge { from, to, label: None, color: None });
+        self.edges.last_mut().unwrap_or_else(|| unreachable!("an edge was just pushed"))
+    }
+
+    /// Adds a cluster, drawn as a labelled box around the nodes put in it with [`Node::cluster`].
+    pub fn cluster(&mut self, id: impl Display, label: impl Display) -> &mut Cluster {
+        self.clusters.push(Cluster { id: id.to_string(), label: label.to_string(), color: None });
+        self.clusters.last_mut().unwrap_or_else(|| unreachable!("a cluster was just pushed"))
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn write_node(&self, f: &mut Formatter<'_>, node: &Node, indent: &str) -> fmt::Result {
+        write!(f, "{indent}{}", quote(&node.id))?;
+        write_attributes(f, &[
+            ("label", node.label.as_deref().map(quote)),
+            ("shape", node.shape.clone()),
+            ("color", node.color.and_then(color)),
+        ])?;
+        writeln!(f, ";")
+    }
+}
+
+impl Node {
+    pub fn label(&mut self, label: impl Display) -> &mut Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// A Graphviz node shape, e.g. `box`, `diamond` or `doublecircle`.
+    pub fn shape(&mut self, shape: &str) -> &mut Self {
+        self.shape = Some(shape.to_string());
+        self
+    }
+
+    /// Puts the node in the cluster `id`, added with [`DotGraph::cluster`].
+    pub fn cluster(&mut self, id: impl Display) -> &mut Self {
+        self.cluster = Some(id.to_string());
+        self
+    }
+}
+
+impl Edge {
+    pub fn label(&mut self, label: impl Display) -> &mut Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl Cluster {
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl Display for DotGraph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (keyword, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        writeln!(f, "{keyword} {} {{", quote(&self.name))?;
+        for (key, value) in &self.attributes {
+            writeln!(f, "    {key}={};", quote(value))?;
+        }
+        for (index, cluster) in self.clusters.iter().enumerate() {
+            // Graphviz only draws subgraphs whose name starts with `cluster` as boxes.
+            writeln!(f, "    subgraph cluster_{index} {{")?;
+            writeln!(f, "        label={};", quote(&cluster.label))?;
+            if let Some(color) = cluster.color.and_then(color) {
+                writeln!(f, "        color={color};")?;
+            }
+            for node in self.nodes.iter().filter(|node| node.cluster.as_ref() == Some(&cluster.id)) {
+                self.write_node(f, node, "        ")?;
+            }
+            writeln!(f, "    }}")?;
+        }
+        let in_cluster = |node: &Node| self.clusters.iter().any(|cluster| node.cluster.as_ref() == Some(&cluster.id));
+        for node in self.nodes.iter().filter(|node| !in_cluster(node)) {
+            self.write_node(f, node, "    ")?;
+        }
+        for edge in &self.edges {
+            write!(f, "    {} {arrow} {}", quote(&edge.from), quote(&edge.to))?;
+            write_attributes(f, &[
+                ("label", edge.label.as_deref().map(quote)),
+                ("color", edge.color.and_then(color)),
+            ])?;
+            writeln!(f, ";")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// Writes ` [key=value, ...]` for the attributes that are set.
+fn write_attributes(f: &mut Formatter<'_>, attributes: &[(&str, Option<String>)]) -> fmt::Result {
+    let set: Vec<String> = attributes
+        .iter()
+        .filter_map(|(key, value)| value.as_ref().map(|value| format!("{key}={value}")))
+        .collect();
+    if set.is_empty() {
+        return Ok(());
+    }
+    write!(f, " [{}]", set.join(", "))
+}
+
+/// A double-quoted DOT identifier, so that any text can be used as an id or a label.
+fn quote(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+/// `Color::Default` leaves the colour to Graphviz, which draws in black.
+fn color(color: Color) -> Option<String> {
+    if color == Color::Default {
+        return None;
+    }
+    let [r, g, b] = color.rgb();
+    Some(format!("\"#{r:02x}{g:02x}{b:02x}\""))
+}
+
+/// Entry point used by the `solution!` macro when a day is run with `--export-graph <path>`.
+pub fn export<G: ExportGraph>(input: &str) {
+    let args: Vec<String> = env::args().collect();
+    let Some(path) = args.iter().position(|x| x == "--export-graph").and_then(|index| args.get(index + 1)) else {
+        eprintln!("Unexpected command-line input. Format: cargo solve 25 --export-graph wires.dot");
+        process::exit(1);
+    };
+    let graph = match G::graph(input) {
+        Ok(graph) => graph,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+    match fs::write(path, graph.to_string()) {
+        Ok(()) => println!("Wrote {} nodes and {} edges to {path}.", graph.nodes(), graph.edges()),
+        Err(e) => {
+            eprintln!("could not write {path}: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_directed_graph() {
+        let mut graph = DotGraph::directed("modules");
+        graph.attribute("rankdir", "LR");
+        graph.node("a").label("%a").shape("box").color(Color::Blue);
+        graph.edge("a", "b").label(3);
+        assert_eq!(
+            graph.to_string(),
+            "digraph \"modules\" {\n    rankdir=\"LR\";\n    \"a\" [label=\"%a\", shape=box, color=\"#4678f0\"];\n    \"b\";\n    \"a\" -> \"b\" [label=\"3\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn writes_clusters_and_escapes() {
+        let mut graph = DotGraph::undirected("wires");
+        graph.cluster("left", "group \"1\"").color(Color::Red);
+        graph.node("x").cluster("left");
+        graph.node("y");
+        graph.node("x").label("a\nb");
+        graph.edge("x", "y");
+        assert_eq!(graph.nodes(), 2);
+        assert_eq!(
+            graph.to_string(),
+            "graph \"wires\" {\n    subgraph cluster_0 {\n        label=\"group \\\"1\\\"\";\n        color=\"#e63c3c\";\n        \"x\" [label=\"a\\nb\"];\n    }\n    \"y\";\n    \"x\" -- \"y\";\n}\n"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+/// Writes timings in formats meant for other tools than the README, with `time --format`.
+use std::path::Path;
+use std::str::FromStr;
+
+use tinyjson::JsonValue;
+
+use crate::template::config::BenchmarkTable;
+use crate::template::readme_benchmarks::construct_table;
+use crate::template::timings::{Memory, Timing, Timings};
+
+/// Output format of `time --format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One row per day, with a header.
+    Csv,
+    /// JSON Lines, one `timings.json` entry per line.
+    Json,
+    /// The README table.
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "json" | "jsonl" => Ok(Format::Json),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(format!("unknown format `{s}`, expected csv, json or markdown.")),
+        }
+    }
+}
+
+impl Format {
+    /// Format matching the extension of `path`, e.g. `timings.csv`.
+    pub fn from_path(path: &str) -> Option<Self> {
+        Path::new(path).extension()?.to_str()?.parse().ok()
+    }
+}
+
+/// `timings` in `format`. Markdown is laid out like the README table, following `table`.
+pub fn export(timings: &Timings, format: Format, table: &BenchmarkTable) -> String {
+    match format {
+        Format::Csv => to_csv(timings),
+        Format::Json => to_json_lines(timings),
+        Format::Markdown => {
+            let mut markdown = construct_table(timings.clone(), table);
+            markdown.push('\n');
+            markdown
+        }
+    }
+}
+
+fn to_csv(timings: &Timings) -> String {
+    let mut lines = vec![
+        "day,part_1,part_2,part_1_nanos,part_2_nanos,total_nanos,part_1_peak_bytes,part_2_peak_bytes".to_string(),
+    ];
+
+    let nanos = |timing: &Timing, part| {
+        timing
+            .part_stats(part)
+            .map_or(String::new(), |stats| stats.mean_nanos.to_string())
+    };
+    let peak = |memory: Option<Memory>| memory.map_or(String::new(), |m| m.peak_bytes.to_string());
+
+    for timing in &timings.data {
+        lines.push(
+            [
+                timing.day.into_inner().to_string(),
+                csv_field(timing.part_1.as_deref().unwrap_or_default()),
+                csv_field(timing.part_2.as_deref().unwrap_or_default()),
+                nanos(timing, 1),
+                nanos(timing, 2),
+                timing.total_nanos.to_string(),
+                peak(timing.memory_1),
+                peak(timing.memory_2),
+            ]
+            .join(","),
+        );
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Quotes a field containing a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn to_json_lines(timings: &Timings) -> String {
+    timings
+        .data
+        .iter()
+        .map(|timing| JsonValue::from(timing).stringify().unwrap_or_default() + "\n")
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    part_1: Some("10.0ms".into()),
+                    part_2: None,
+                    total_nanos: 1e+7,
+                    memory_1: Some(Memory { peak_bytes: 2048, total_bytes: 4096, allocations: 3 }),
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
+                },
+                Timing {
+                    day: day!(2),
+                    part_1: Some("1.5µs".into()),
+                    part_2: Some("2.0ms".into()),
+                    total_nanos: 2001500.0,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!("jsonl".parse(), Ok(Format::Json));
+        assert_eq!("md".parse(), Ok(Format::Markdown));
+        assert!("xml".parse::<Format>().is_err());
+        assert_eq!(Format::from_path("wiki/timings.md"), Some(Format::Markdown));
+        assert_eq!(Format::from_path("timings"), None);
+    }
+
+    #[test]
+    fn exports_csv() {
+        let csv = export(&get_mock_timings(), Format::Csv, &BenchmarkTable::default());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "1,10.0ms,,10000000,,10000000,2048,");
+        assert_eq!(lines[2], "2,1.5µs,2.0ms,1500,2000000,2001500,,");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn exports_json_lines() {
+        let json = export(&get_mock_timings(), Format::Json, &BenchmarkTable::default());
+        let timings: Vec<Timing> = json
+            .lines()
+            .map(|line| Timing::try_from(&line.parse::<JsonValue>().unwrap()).unwrap())
+            .collect();
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].memory_1.map(|m| m.peak_bytes), Some(2048));
+        assert_eq!(timings[1].part_2.as_deref(), Some("2.0ms"));
+    }
+
+    #[test]
+    fn exports_markdown() {
+        let markdown = export(&get_mock_timings(), Format::Markdown, &BenchmarkTable::default());
+        assert!(markdown.starts_with("## Benchmarks\n"));
+        assert!(markdown.contains("| [Day 2](./src/bin/02.rs) | `1.5µs` | `2.0ms` | `-` / `-` |"));
+        assert!(!markdown.contains("<!---"));
+    }
+}
@@ -0,0 +1,373 @@
+/// Append-only record of every `cargo time` run, used to catch performance regressions.
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::env;
+
+use tinyjson::JsonValue;
+
+use crate::template::config::Config;
+use crate::template::run_multi::child_commands::parse_duration;
+use crate::template::timings::Timings;
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+/// Where and when a set of benchmarks was run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunInfo {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Commit checked out in `.git/HEAD`, if any.
+    pub commit: Option<String>,
+    pub host: Option<String>,
+}
+
+/// Benchmark of a single day in one run, one line of the history file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub run: RunInfo,
+    pub day: Day,
+    pub part_1_nanos: Option<f64>,
+    pub part_2_nanos: Option<f64>,
+}
+
+/// Every benchmark ever stored, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl RunInfo {
+    pub fn current() -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        RunInfo {
+            timestamp,
+            commit: git_commit(Path::new(".git")),
+            host: host_name(),
+        }
+    }
+
+    /// UTC date and time of the run, e.g. `2023-12-22 06:30`.
+    pub fn date(&self) -> String {
+        format_timestamp(self.timestamp)
+    }
+}
+
+/// UTC date and time of a Unix timestamp, e.g. `2023-12-22 06:30`.
+pub fn format_timestamp(timestamp: u64) -> String {
+    // days to civil date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = timestamp / 86_400;
+    let seconds = timestamp % 86_400;
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+impl HistoryEntry {
+    /// One entry per benchmarked day of `timings`.
+    pub fn from_timings(timings: &Timings, run: &RunInfo) -> Vec<Self> {
+        timings
+            .data
+            .iter()
+            .map(|timing| HistoryEntry {
+                run: run.clone(),
+                day: timing.day,
+                part_1_nanos: timing.part_1.as_deref().and_then(parse_duration),
+                part_2_nanos: timing.part_2.as_deref().and_then(parse_duration),
+            })
+            .filter(|entry| entry.part_1_nanos.is_some() || entry.part_2_nanos.is_some())
+            .collect()
+    }
+
+    pub fn part_nanos(&self, part: u8) -> Option<f64> {
+        match part {
+            1 => self.part_1_nanos,
+            2 => self.part_2_nanos,
+            _ => None,
+        }
+    }
+}
+
+impl History {
+    /// Rehydrate the history from its JSON Lines file. If not present, returns an empty history.
+    pub fn read_from_file() -> Self {
+        let path = Config::get().paths.history();
+        let Ok(s) = fs::read_to_string(&path) else {
+            return History::default();
+        };
+        let mut entries = vec![];
+        for (index, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            match HistoryEntry::try_from(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!("{}:{}: {e}", path.display(), index + 1),
+            }
+        }
+        History { entries }
+    }
+
+    /// Append entries to the history file, never rewriting earlier runs.
+    pub fn append(entries: &[HistoryEntry]) -> Result<(), io::Error> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Config::get().paths.history())?;
+        for entry in entries {
+            let json = JsonValue::from(entry).stringify().map_err(io::Error::other)?;
+            writeln!(file, "{json}")?;
+        }
+        Ok(())
+    }
+
+    /// The most recent entry of each day.
+    pub fn latest(&self) -> Vec<HistoryEntry> {
+        let mut latest: Vec<HistoryEntry> = vec![];
+        for entry in &self.entries {
+            match latest.iter_mut().find(|e| e.day == entry.day) {
+                Some(e) => *e = entry.clone(),
+                None => latest.push(entry.clone()),
+            }
+        }
+        latest.sort_unstable_by_key(|e| e.day);
+        latest
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Timing of one part in two runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Comparison {
+    pub day: Day,
+    pub part: u8,
+    pub before_nanos: f64,
+    pub after_nanos: f64,
+}
+
+impl Comparison {
+    pub fn ratio(&self) -> f64 {
+        self.after_nanos / self.before_nanos
+    }
+
+    /// Whether the part got slower by more than `threshold` percent.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.ratio() > 1.0 + threshold / 100.0
+    }
+}
+
+/// Pairs the parts of `after` with the same parts in `before`. Parts missing from either side are skipped.
+pub fn compare(before: &[HistoryEntry], after: &[HistoryEntry]) -> Vec<Comparison> {
+    let mut comparisons = vec![];
+    for entry in after {
+        let Some(previous) = before.iter().find(|e| e.day == entry.day) else {
+            continue;
+        };
+        for part in [1, 2] {
+            if let (Some(before_nanos), Some(after_nanos)) = (previous.part_nanos(part), entry.part_nanos(part)) {
+                if before_nanos > 0.0 {
+                    comparisons.push(Comparison { day: entry.day, part, before_nanos, after_nanos });
+                }
+            }
+        }
+    }
+    comparisons
+}
+
+/// Prints one line per compared part and returns the number of regressions.
+pub fn print_comparisons(comparisons: &[Comparison], threshold: f64) -> usize {
+    if comparisons.is_empty() {
+        println!("No previous benchmarks to compare with.");
+        return 0;
+    }
+
+    println!("{ANSI_BOLD}Compared to previous run{ANSI_RESET} (threshold: {threshold}%)");
+    let mut regressions = 0;
+    for comparison in comparisons {
+        let flag = if comparison.is_regression(threshold) {
+            regressions += 1;
+            " ✖ regression"
+        } else {
+            ""
+        };
+        println!(
+            "Day {} part {}: {} -> {} ({:+.1}%){flag}",
+            comparison.day,
+            comparison.part,
+            format_nanos(comparison.before_nanos),
+            format_nanos(comparison.after_nanos),
+            (comparison.ratio() - 1.0) * 100.0
+        );
+    }
+    regressions
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Hash of the commit checked out in the repository at `git_dir`, following `HEAD` through
+/// loose and packed refs.
+fn git_commit(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    let Some(reference) = head.strip_prefix("ref: ") else {
+        return Some(head.to_string());
+    };
+    if let Ok(hash) = fs::read_to_string(git_dir.join(reference)) {
+        return Some(hash.trim().to_string());
+    }
+    // refs are moved to `packed-refs` by `git gc`.
+    fs::read_to_string(git_dir.join("packed-refs"))
+        .ok()?
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .find(|(_, name)| *name == reference)
+        .map(|(hash, _)| hash.to_string())
+}
+
+fn host_name() -> Option<String> {
+    env::var("HOSTNAME")
+        .or_else(|_| env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn optional(value: Option<JsonValue>) -> JsonValue {
+    value.unwrap_or(JsonValue::Null)
+}
+
+impl From<&HistoryEntry> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &HistoryEntry) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("timestamp".into(), JsonValue::Number(value.run.timestamp as f64));
+        map.insert("commit".into(), optional(value.run.commit.clone().map(JsonValue::String)));
+        map.insert("host".into(), optional(value.run.host.clone().map(JsonValue::String)));
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part_1_nanos".into(), optional(value.part_1_nanos.map(JsonValue::Number)));
+        map.insert("part_2_nanos".into(), optional(value.part_2_nanos.map(JsonValue::Number)));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&str> for HistoryEntry {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON."))?;
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected history entry to be a JSON object.")?;
+
+        let string = |key: &str| json.get(key).and_then(|v| v.get::<String>()).cloned();
+        let number = |key: &str| json.get(key).and_then(|v| v.get::<f64>()).copied();
+
+        let day = string("day")
+            .and_then(|day| Day::from_str(&day).ok())
+            .ok_or("expected entry.day to be a Day struct.")?;
+        let timestamp = number("timestamp").ok_or("expected entry.timestamp to be a number.")?;
+
+        Ok(HistoryEntry {
+            run: RunInfo {
+                timestamp: timestamp as u64,
+                commit: string("commit"),
+                host: string("host"),
+            },
+            day,
+            part_1_nanos: number("part_1_nanos"),
+            part_2_nanos: number("part_2_nanos"),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    fn entry(day: Day, part_1_nanos: Option<f64>, part_2_nanos: Option<f64>) -> HistoryEntry {
+        HistoryEntry {
+            run: RunInfo { timestamp: 1_700_000_000, commit: Some("abc123".into()), host: None },
+            day,
+            part_1_nanos,
+            part_2_nanos,
+        }
+    }
+
+    #[test]
+    fn round_trips_entries() {
+        let entry = entry(day!(22), Some(1500.0), None);
+        let line = JsonValue::from(&entry).stringify().unwrap();
+        assert_eq!(HistoryEntry::try_from(line.as_str()), Ok(entry));
+        assert!(HistoryEntry::try_from(r#"{ "day": "22" }"#).is_err());
+    }
+
+    #[test]
+    fn formats_run_date() {
+        let run = |timestamp| RunInfo { timestamp, commit: None, host: None };
+        assert_eq!(run(0).date(), "1970-01-01 00:00");
+        assert_eq!(run(1_703_226_600).date(), "2023-12-22 06:30");
+        assert_eq!(run(1_709_210_096).date(), "2024-02-29 12:34");
+    }
+
+    #[test]
+    fn keeps_latest_entry_per_day() {
+        let history = History {
+            entries: vec![entry(day!(2), Some(1.0), None), entry(day!(1), Some(2.0), None), entry(day!(2), Some(3.0), None)],
+        };
+        let latest = history.latest();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[1].part_1_nanos, Some(3.0));
+    }
+
+    #[test]
+    fn flags_regressions() {
+        let before = [entry(day!(1), Some(100.0), Some(100.0)), entry(day!(2), Some(100.0), None)];
+        let after = [entry(day!(1), Some(105.0), Some(150.0)), entry(day!(2), None, Some(1.0)), entry(day!(3), Some(1.0), None)];
+        let comparisons = compare(&before, &after);
+        assert_eq!(comparisons.len(), 2);
+        assert!(!comparisons[0].is_regression(10.0));
+        assert!(comparisons[1].is_regression(10.0));
+        assert_eq!(comparisons[1].part, 2);
+    }
+
+    #[test]
+    fn reads_git_commit() {
+        let dir = env::temp_dir().join(format!("aoc-history-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("refs/heads")).unwrap();
+        fs::write(dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(dir.join("packed-refs"), "# pack-refs\n0123abcd refs/heads/main\n").unwrap();
+        assert_eq!(git_commit(&dir), Some("0123abcd".into()));
+        fs::write(dir.join("refs/heads/main"), "4567ef\n").unwrap();
+        assert_eq!(git_commit(&dir), Some("4567ef".into()));
+        fs::write(dir.join("HEAD"), "89abcd\n").unwrap();
+        assert_eq!(git_commit(&dir), Some("89abcd".into()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
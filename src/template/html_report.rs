@@ -0,0 +1,308 @@
+/// Standalone HTML benchmark report, written with `time --html <path>`.
+/// Charts are inline SVG generated here, so the page needs neither scripts nor network access.
+use std::fmt::Write;
+
+use crate::template::config::Unit;
+use crate::template::history::{format_nanos, History, HistoryEntry, RunInfo};
+use crate::template::timings::{format_bytes, Memory, Timing, Timings};
+
+const PART_COLORS: [&str; 2] = ["#4678f0", "#e6a23c"];
+
+const LABEL_WIDTH: f64 = 70.0;
+const CHART_WIDTH: f64 = 640.0;
+const ROW_HEIGHT: f64 = 30.0;
+const AXIS_HEIGHT: f64 = 30.0;
+
+const SPARKLINE_WIDTH: f64 = 160.0;
+const SPARKLINE_HEIGHT: f64 = 32.0;
+
+/// The report page, with the total written in `total_unit` like the readme table.
+pub fn report(timings: &Timings, history: &History, run: &RunInfo, total_unit: Unit) -> String {
+    let mut html = String::new();
+    let mut subtitle = format!("Generated on {} UTC", run.date());
+    if let Some(commit) = &run.commit {
+        let _ = write!(subtitle, " at commit <code>{}</code>", escape(&commit[..commit.len().min(7)]));
+    }
+    if let Some(host) = &run.host {
+        let _ = write!(subtitle, " on {}", escape(host));
+    }
+
+    html.push_str(concat!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>Benchmarks</title>\n<style>\n",
+        "body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 60em; color: #222; }\n",
+        "table { border-collapse: collapse; margin: 1em 0; }\n",
+        "th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; text-align: right; }\n",
+        "th:first-child, td:first-child { text-align: left; }\n",
+        "svg text { font-size: 12px; fill: #444; }\n",
+        ".legend span { display: inline-block; width: 0.8em; height: 0.8em; margin: 0 0.3em 0 1em; }\n",
+        "</style>\n</head>\n<body>\n<h1>Benchmarks</h1>\n",
+    ));
+    let _ = writeln!(html, "<p>{subtitle}</p>");
+
+    if timings.data.is_empty() {
+        html.push_str("<p>No timings stored yet, run <code>cargo time</code> first.</p>\n");
+    } else {
+        let _ = writeln!(
+            html,
+            "<p class=\"legend\"><span style=\"background: {}\"></span>Part 1<span style=\"background: {}\"></span>Part 2</p>",
+            PART_COLORS[0], PART_COLORS[1]
+        );
+        html.push_str(&bar_chart(timings));
+        html.push_str(&table(timings, history));
+        let total_nanos: f64 = timings.data.iter().map(|t| t.total_nanos).sum();
+        let _ = writeln!(html, "<p><strong>Total: {}</strong></p>", total_unit.format(total_nanos));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Horizontal bars of both parts of each day, on a log scale.
+fn bar_chart(timings: &Timings) -> String {
+    let values = timings
+        .data
+        .iter()
+        .flat_map(|timing| [timing.part_stats(1), timing.part_stats(2)])
+        .flatten()
+        .map(|stats| stats.mean_nanos);
+    let scale = LogScale::new(values, CHART_WIDTH);
+
+    #[allow(clippy::cast_precision_loss)]
+    let height = timings.data.len() as f64 * ROW_HEIGHT + AXIS_HEIGHT;
+    let width = LABEL_WIDTH + CHART_WIDTH + 10.0;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    );
+
+    for decade in scale.decades() {
+        let x = LABEL_WIDTH + scale.x(10_f64.powi(decade));
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x:.1}\" y1=\"0\" x2=\"{x:.1}\" y2=\"{:.1}\" stroke=\"#ddd\"/><text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            height - AXIS_HEIGHT,
+            height - AXIS_HEIGHT + 18.0,
+            decade_label(decade)
+        );
+    }
+
+    for (row, timing) in timings.data.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let y = row as f64 * ROW_HEIGHT;
+        let _ = writeln!(
+            svg,
+            "<text x=\"0\" y=\"{:.1}\">Day {}</text>",
+            y + ROW_HEIGHT / 2.0 + 4.0,
+            timing.day.into_inner()
+        );
+        for (part, color) in [1, 2].into_iter().zip(PART_COLORS) {
+            let Some(stats) = timing.part_stats(part) else {
+                continue;
+            };
+            let bar_y = y + 4.0 + f64::from(part - 1) * 11.0;
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{LABEL_WIDTH}\" y=\"{bar_y:.1}\" width=\"{:.1}\" height=\"10\" fill=\"{color}\"><title>Day {} part {part}: {}</title></rect>",
+                scale.x(stats.mean_nanos).max(1.0),
+                timing.day.into_inner(),
+                format_nanos(stats.mean_nanos)
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn table(timings: &Timings, history: &History) -> String {
+    let has_memory = timings.has_memory();
+    let has_history = timings.data.iter().any(|timing| trend_of(history, timing).len() > 1);
+
+    let mut html = String::from("<table>\n<tr><th>Day</th><th>Part 1</th><th>Part 2</th>");
+    if has_memory {
+        html.push_str("<th>Peak memory</th><th>Allocations</th>");
+    }
+    if has_history {
+        html.push_str("<th>Trend</th>");
+    }
+    html.push_str("</tr>\n");
+
+    for timing in &timings.data {
+        let _ = write!(
+            html,
+            "<tr><td>Day {}</td><td>{}</td><td>{}</td>",
+            timing.day.into_inner(),
+            escape(timing.part_1.as_deref().unwrap_or("-")),
+            escape(timing.part_2.as_deref().unwrap_or("-"))
+        );
+        if has_memory {
+            let peak = |memory: Option<Memory>| memory.map_or("-".into(), |m| format_bytes(m.peak_bytes));
+            let allocations = |memory: Option<Memory>| memory.map_or("-".into(), |m| m.allocations.to_string());
+            let _ = write!(
+                html,
+                "<td>{} / {}</td><td>{} / {}</td>",
+                peak(timing.memory_1),
+                peak(timing.memory_2),
+                allocations(timing.memory_1),
+                allocations(timing.memory_2)
+            );
+        }
+        if has_history {
+            let _ = write!(html, "<td>{}</td>", sparkline(&trend_of(history, timing)));
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n");
+    html
+}
+
+fn trend_of<'a>(history: &'a History, timing: &Timing) -> Vec<&'a HistoryEntry> {
+    history.entries.iter().filter(|entry| entry.day == timing.day).collect()
+}
+
+/// Both parts of a day over its stored runs, oldest on the left, on a log scale.
+fn sparkline(entries: &[&HistoryEntry]) -> String {
+    if entries.len() < 2 {
+        return String::new();
+    }
+    let values = entries.iter().flat_map(|entry| [entry.part_1_nanos, entry.part_2_nanos]).flatten();
+    let scale = LogScale::new(values, SPARKLINE_HEIGHT - 4.0);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SPARKLINE_WIDTH}\" height=\"{SPARKLINE_HEIGHT}\">"
+    );
+    #[allow(clippy::cast_precision_loss)]
+    let step = SPARKLINE_WIDTH / (entries.len() - 1) as f64;
+    for (part, color) in [1, 2].into_iter().zip(PART_COLORS) {
+        #[allow(clippy::cast_precision_loss)]
+        let points: Vec<String> = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let y = SPARKLINE_HEIGHT - 2.0 - scale.x(entry.part_nanos(part)?);
+                Some(format!("{:.1},{y:.1}", index as f64 * step))
+            })
+            .collect();
+        if points.len() > 1 {
+            let _ = write!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\"/>",
+                points.join(" ")
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Maps nanoseconds to `0..=length`, one decade being the same length wherever it is.
+struct LogScale {
+    min_decade: i32,
+    max_decade: i32,
+    length: f64,
+}
+
+impl LogScale {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(values: impl Iterator<Item = f64>, length: f64) -> Self {
+        let (min, max) = values
+            .filter(|v| *v > 0.0)
+            .fold((f64::MAX, f64::MIN), |(min, max), v| (min.min(v), max.max(v)));
+        if min > max {
+            return LogScale { min_decade: 0, max_decade: 1, length };
+        }
+        let min_decade = min.log10().floor() as i32;
+        let max_decade = (max.log10().ceil() as i32).max(min_decade + 1);
+        LogScale { min_decade, max_decade, length }
+    }
+
+    fn x(&self, nanos: f64) -> f64 {
+        let position = (nanos.max(1.0).log10() - f64::from(self.min_decade))
+            / f64::from(self.max_decade - self.min_decade);
+        position.clamp(0.0, 1.0) * self.length
+    }
+
+    fn decades(&self) -> impl Iterator<Item = i32> {
+        self.min_decade..=self.max_decade
+    }
+}
+
+/// `10^decade` nanoseconds, e.g. `100ns`, `1µs` or `10s`.
+fn decade_label(decade: i32) -> String {
+    let units = ["ns", "µs", "ms", "s"];
+    let unit = usize::try_from(decade.max(0) / 3).unwrap_or_default().min(units.len() - 1);
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    let exponent = decade - 3 * unit as i32;
+    format!("{}{}", 10_u64.pow(exponent.max(0).unsigned_abs()), units[unit])
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+    use crate::template::timings::PartStats;
+
+    fn timing(part_1: f64, part_2: Option<f64>) -> Timing {
+        let stats = |mean_nanos| PartStats { mean_nanos, std_dev_nanos: 0.0, samples: 10 };
+        Timing {
+            day: day!(22),
+            part_1: Some(format_nanos(part_1)),
+            part_2: part_2.map(format_nanos),
+            total_nanos: part_1 + part_2.unwrap_or_default(),
+            memory_1: None,
+            memory_2: None,
+            stats_1: Some(stats(part_1)),
+            stats_2: part_2.map(stats),
+            read_input: None,
+            parse: None,
+        }
+    }
+
+    fn entry(part_1_nanos: f64) -> HistoryEntry {
+        HistoryEntry {
+            run: RunInfo { timestamp: 0, commit: None, host: None },
+            day: day!(22),
+            part_1_nanos: Some(part_1_nanos),
+            part_2_nanos: None,
+        }
+    }
+
+    #[test]
+    fn scales_logarithmically() {
+        let scale = LogScale::new([150.0, 2e6].into_iter(), 500.0);
+        assert_eq!((scale.min_decade, scale.max_decade), (2, 7));
+        assert!((scale.x(1e3) - 100.0).abs() < 1e-9);
+        assert_eq!(scale.x(1.0), 0.0);
+        assert_eq!(scale.decades().map(decade_label).collect::<Vec<_>>(), ["100ns", "1µs", "10µs", "100µs", "1ms", "10ms"]);
+    }
+
+    #[test]
+    fn writes_standalone_report() {
+        let timings = Timings { data: vec![timing(174e6, Some(1.5e3))] };
+        let run = RunInfo { timestamp: 1_703_226_600, commit: Some("2be414ce24e0".into()), host: Some("<vm>".into()) };
+        let html = report(&timings, &History::default(), &run, Unit::Millis);
+        assert!(html.contains("<p><strong>Total: 174.00ms</strong></p>"));
+        assert!(html.contains("Generated on 2023-12-22 06:30 UTC at commit <code>2be414c</code> on &lt;vm&gt;"));
+        assert_eq!(html.matches("<rect").count(), 2);
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("Trend"));
+        assert!(!html.contains("Peak memory"));
+    }
+
+    #[test]
+    fn includes_trend_and_memory() {
+        let mut timings = Timings { data: vec![timing(174e6, None)] };
+        timings.data[0].memory_1 = Some(Memory { peak_bytes: 2048, total_bytes: 4096, allocations: 3 });
+        let history = History { entries: vec![entry(180e6), entry(170e6), entry(174e6)] };
+        let run = RunInfo { timestamp: 0, commit: None, host: None };
+        let html = report(&timings, &history, &run, Unit::Seconds);
+        assert!(html.contains("<p><strong>Total: 0.17s</strong></p>"));
+        assert!(html.contains("<td>2.0 KiB / -</td><td>3 / -</td>"));
+        assert_eq!(html.matches("<polyline").count(), 1);
+    }
+}
@@ -1,57 +1,145 @@
-use std::{env, fs};
+use std::error::Error;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::{env, fs, io};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod dot;
 pub mod runner;
+pub mod visualize;
 
 pub use day::*;
 
+use config::Config;
+
+mod answers;
+mod config;
 mod day;
+mod export;
+mod history;
+mod html_report;
 mod readme_benchmarks;
+mod readme_stars;
 mod run_multi;
+mod status;
 mod timings;
+mod watch;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// Error returned when an input or example file can't be read.
+#[derive(Debug)]
+pub struct ReadError {
+    pub path: PathBuf,
+    pub folder: String,
+    pub day: Day,
+    pub source: io::Error,
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not open {}: {}", self.path.display(), self.source)?;
+        if self.folder == "inputs" && self.source.kind() == io::ErrorKind::NotFound {
+            write!(f, " (did you run `cargo download {}`?)", self.day.into_inner())?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ReadError {}
+
+fn read_path(folder: &str, day: Day, file_name: String) -> Result<String, ReadError> {
+    let path = env::current_dir()
+        .map_err(|source| ReadError { path: file_name.clone().into(), folder: folder.into(), day, source })?
+        .join(Config::get().paths.folder(folder))
+        .join(file_name);
+    fs::read_to_string(&path).map_err(|source| ReadError { path, folder: folder.into(), day, source })
+}
+
+/// Reads `data/{folder}/{day}.txt` to a string, or `{day}.txt` in the folder set in `aoc.toml`.
+pub fn try_read_file(folder: &str, day: Day) -> Result<String, ReadError> {
+    read_path(folder, day, format!("{day}.txt"))
+}
+
+/// Reads `data/{folder}/{day}-{part}.txt` to a string. E.g. like `01-2.txt`.
+pub fn try_read_file_part(folder: &str, day: Day, part: u8) -> Result<String, ReadError> {
+    read_path(folder, day, format!("{day}-{part}.txt"))
+}
+
+/// Like [`try_read_file`], with the input passed through [`normalize`].
+pub fn try_read_file_normalized(folder: &str, day: Day) -> Result<String, ReadError> {
+    try_read_file(folder, day).map(|input| normalize(&input))
+}
+
+/// Strips carriage returns and the trailing newlines, so that inputs saved with Windows line
+/// endings or an extra blank line split cleanly on `'\n'`.
+#[must_use]
+pub fn normalize(input: &str) -> String {
+    input.replace('\r', "").trim_end_matches('\n').to_string()
+}
+
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    try_read_file(folder, day).unwrap_or_else(|e| panic!("{e}"))
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    try_read_file_part(folder, day, part).unwrap_or_else(|e| panic!("{e}"))
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// Passing `normalized` last reads the input through [`normalize`] first.
+/// Passing `visualize = Type` lets `cargo visualize` play the frames of a [`visualize::Visualize`] implementation.
+/// Passing `graph = Type` lets `cargo solve DAY --export-graph <path>` write the DOT graph of a [`dot::ExportGraph`] implementation.
+/// Passing `parse = function` parses the input once with `function` and hands a reference to the parsed value to both parts.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+        $crate::solution!(@impl $day, try_read_file, [], [], [], [part_one, 1] [part_two, 2]);
     };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(@impl $day, try_read_file, [], [], [], [part_one, 1]);
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(@impl $day, try_read_file, [], [], [], [part_two, 2]);
+    };
+    ($day:expr, normalized) => {
+        $crate::solution!(@impl $day, try_read_file_normalized, [], [], [], [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, 1, normalized) => {
+        $crate::solution!(@impl $day, try_read_file_normalized, [], [], [], [part_one, 1]);
+    };
+    ($day:expr, 2, normalized) => {
+        $crate::solution!(@impl $day, try_read_file_normalized, [], [], [], [part_two, 2]);
+    };
+    ($day:expr, visualize = $vis:ty) => {
+        $crate::solution!(@impl $day, try_read_file, [$vis], [], [], [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, normalized, visualize = $vis:ty) => {
+        $crate::solution!(@impl $day, try_read_file_normalized, [$vis], [], [], [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, graph = $graph:ty) => {
+        $crate::solution!(@impl $day, try_read_file, [], [$graph], [], [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, normalized, graph = $graph:ty) => {
+        $crate::solution!(@impl $day, try_read_file_normalized, [], [$graph], [], [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, parse = $parse:path) => {
+        $crate::solution!(@impl $day, try_read_file, [], [], [$parse], [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, visualize = $vis:ty, parse = $parse:path) => {
+        $crate::solution!(@impl $day, try_read_file, [$vis], [], [$parse], [part_one, 1] [part_two, 2]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $reader:ident, [$($vis:ty)?], [$($graph:ty)?], [$($parse:path)?], $( [$func:expr, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -61,8 +149,51 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
+            let input = read_input(|| $crate::template::$reader("inputs", DAY));
+            if std::env::args().any(|x| x == "--visualize") {
+                $(
+                    $crate::template::visualize::run::<$vis>(&input);
+                    return;
+                )?
+                #[allow(unreachable_code)]
+                {
+                    eprintln!("day {DAY} has no visualisation, see `solution!(DAY, visualize = ...)`.");
+                    std::process::exit(1);
+                }
+            }
+            if std::env::args().any(|x| x == "--export-graph") {
+                let export: Option<fn(&str)> = None $( .or(Some($crate::template::dot::export::<$graph>)) )?;
+                let Some(export) = export else {
+                    eprintln!("day {DAY} has no graph to export, see `solution!(DAY, graph = ...)`.");
+                    std::process::exit(1);
+                };
+                export(&input);
+            }
+            $( let input = parse_input($parse, &input); )?
             $( run_part($func, &input, DAY, $part); )*
         }
     };
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_line_endings() {
+        assert_eq!(normalize("#.?\r\n.#?\r\n\r\n"), "#.?\n.#?");
+        assert_eq!(normalize("#.?\n.#?"), "#.?\n.#?");
+    }
+
+    #[test]
+    fn hints_at_download_for_missing_inputs() {
+        let error = |folder: &str| ReadError {
+            path: PathBuf::from(format!("data/{folder}/12.txt")),
+            folder: folder.into(),
+            day: crate::day!(12),
+            source: io::ErrorKind::NotFound.into(),
+        };
+        assert!(error("inputs").to_string().ends_with("(did you run `cargo download 12`?)"));
+        assert!(!error("examples").to_string().contains("cargo download"));
+    }
+}
@@ -2,7 +2,8 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
-use crate::template::timings::Timings;
+use crate::template::config::{BenchmarkTable, Column, Sort};
+use crate::template::timings::{format_bytes, Memory, PartStats, Timing, Timings};
 use crate::template::Day;
 
 static MARKER: &str = "<!--- benchmarking table --->";
@@ -13,6 +14,15 @@ pub enum Error {
     IO(io::Error),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(e) => write!(f, "{e}"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IO(e)
@@ -20,8 +30,8 @@ impl From<std::io::Error> for Error {
 }
 
 pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+    pub pos_start: usize,
+    pub pos_end: usize,
 }
 
 #[must_use]
@@ -29,8 +39,9 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+/// Position of the table enclosed by the two occurrences of `marker`, markers included.
+pub fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -51,47 +62,86 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
+/// Peak heap usage of both parts, e.g. `` `1.5 KiB` / `2.0 MiB` ``.
+fn format_memory(timing: &Timing) -> String {
+    let peak = |memory: Option<Memory>| memory.map_or_else(|| "-".into(), |m| format_bytes(m.peak_bytes));
+    format!("`{}` / `{}`", peak(timing.memory_1), peak(timing.memory_2))
+}
+
+/// Benchmark samples of both parts, e.g. `` `1000` / `12` ``. Parts timed without `--time` have no
+/// samples recorded.
+fn format_samples(timing: &Timing) -> String {
+    let samples = |stats: Option<PartStats>| stats.map_or_else(|| "-".into(), |s| s.samples.to_string());
+    format!("`{}` / `{}`", samples(timing.stats_1), samples(timing.stats_2))
+}
+
+/// Title of an extra column and the function writing its cells.
+type ExtraColumn = (&'static str, fn(&Timing) -> String);
+
+/// The extra `column`, `None` if no day has the data yet.
+fn column(column: Column, timings: &Timings) -> Option<ExtraColumn> {
+    let has_data = |has: fn(&Timing) -> bool| timings.data.iter().any(has);
+    match column {
+        Column::Samples => has_data(|t| t.stats_1.is_some() || t.stats_2.is_some()).then_some(("Samples", format_samples)),
+        Column::Memory => timings.has_memory().then_some(("Memory", format_memory)),
+        Column::ReadInput => has_data(|t| t.read_input.is_some())
+            .then_some(("Read input", |t: &Timing| format!("`{}`", t.read_input.as_deref().unwrap_or("-")))),
+        Column::Parse => has_data(|t| t.parse.is_some())
+            .then_some(("Parse", |t: &Timing| format!("`{}`", t.parse.as_deref().unwrap_or("-")))),
+    }
+}
+
+/// Markdown table of `timings` under a `Benchmarks` heading laid out by `config`, without the README markers.
+pub fn construct_table(timings: Timings, config: &BenchmarkTable) -> String {
+    let total_nanos: f64 = timings.data.iter().map(|t| t.total_nanos).sum();
+    let columns: Vec<_> = config.columns.iter().filter_map(|c| column(*c, &timings)).collect();
+
+    let mut data = timings.data;
+    let mut by_total: Vec<&Timing> = data.iter().collect();
+    by_total.sort_by(|a, b| b.total_nanos.total_cmp(&a.total_nanos));
+    let slowest: Vec<Day> = by_total.iter().take(config.highlight_slowest).map(|t| t.day).collect();
+    if config.sort == Sort::Total {
+        data.sort_by(|a, b| b.total_nanos.total_cmp(&a.total_nanos));
+    }
 
     let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
+        format!("{} Benchmarks", config.heading()),
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        format!("| Day | Part 1 | Part 2 |{}", columns.iter().map(|(title, _)| format!(" {title} |")).collect::<String>()),
+        format!("| :---: | :---: | :---:  |{}", " :---: |".repeat(columns.len())),
     ];
 
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+    for timing in &data {
+        let mut cells = vec![
+            format!("[Day {}]({})", timing.day.into_inner(), get_path_for_bin(timing.day)),
+            format!("`{}`", timing.part_1.as_deref().unwrap_or("-")),
+            format!("`{}`", timing.part_2.as_deref().unwrap_or("-")),
+        ];
+        cells.extend(columns.iter().map(|(_, cell)| cell(timing)));
+        if slowest.contains(&timing.day) {
+            cells = cells.into_iter().map(|cell| format!("**{cell}**")).collect();
+        }
+        lines.push(format!("| {} |", cells.join(" | ")));
     }
 
     lines.push(String::new());
-    lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+    lines.push(format!("**Total: {}**", config.total_unit.format(total_nanos)));
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+fn update_content(s: &mut String, timings: Timings, config: &BenchmarkTable) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = format!("{MARKER}\n{}\n{MARKER}", construct_table(timings, config));
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
-pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
+/// Writes the table to the readme set in `config`.
+pub fn update(timings: Timings, config: &BenchmarkTable) -> Result<(), Error> {
+    let path = &config.readme;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+    update_content(&mut readme, timings, config)?;
     fs::write(path, &readme)?;
     Ok(())
 }
@@ -99,7 +149,9 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::template::config::{BenchmarkTable, Column, Sort, Unit};
+    use crate::template::timings::PartStats;
+    use crate::{day, template::timings::Memory, template::timings::Timing, template::timings::Timings};
 
     fn get_mock_timings() -> Timings {
         Timings {
@@ -108,19 +160,37 @@ mod tests {
                     day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    total_nanos: 3e+7,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    total_nanos: 7e+7,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
-                    total_nanos: 9e+10,
+                    total_nanos: 9e+7,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 },
             ],
         }
@@ -130,28 +200,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), &BenchmarkTable::default()).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), &BenchmarkTable::default()).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), &BenchmarkTable::default()).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), &BenchmarkTable::default()).unwrap();
+        update_content(&mut s, get_mock_timings(), &BenchmarkTable::default()).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -159,7 +229,7 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), &BenchmarkTable::default()).unwrap();
         let expected = [
             "foo",
             "bar",
@@ -179,4 +249,40 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_memory_column() {
+        let mut timings = get_mock_timings();
+        timings.data[0].memory_1 = Some(Memory { peak_bytes: 1536, total_bytes: 4096, allocations: 3 });
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, timings, &BenchmarkTable::default()).unwrap();
+        assert!(s.contains("| Day | Part 1 | Part 2 | Memory |"));
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `1.5 KiB` / `-` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | `-` / `-` |"));
+    }
+
+    #[test]
+    fn format_configured_table() {
+        let mut timings = get_mock_timings();
+        timings.data[0].stats_1 = Some(PartStats { mean_nanos: 1e7, std_dev_nanos: 0.0, samples: 100 });
+        timings.data[2].read_input = Some("1.2µs".into());
+        let config = BenchmarkTable {
+            readme: "README.md".into(),
+            heading_level: 3,
+            columns: vec![Column::ReadInput, Column::Samples, Column::Memory],
+            sort: Sort::Total,
+            highlight_slowest: 1,
+            total_unit: Unit::Seconds,
+        };
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, timings, &config).unwrap();
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(lines[1], "### Benchmarks");
+        assert_eq!(lines[3], "| Day | Part 1 | Part 2 | Read input | Samples |");
+        assert_eq!(lines[4], "| :---: | :---: | :---:  | :---: | :---: |");
+        assert_eq!(lines[5], "| **[Day 4](./src/bin/04.rs)** | **`40ms`** | **`50ms`** | **`1.2µs`** | **`-` / `-`** |");
+        assert_eq!(lines[6], "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | `-` | `-` / `-` |");
+        assert_eq!(lines[7], "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `-` | `100` / `-` |");
+        assert_eq!(lines[9], "**Total: 0.19s**");
+    }
 }
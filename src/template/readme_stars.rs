@@ -0,0 +1,383 @@
+/// Module that updates the readme with the stars of solved days, from a completion record kept in
+/// `data/stars.json` instead of the `aoc-readme-stars` action and the private leaderboard.
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fs, io};
+
+use tinyjson::JsonValue;
+
+use crate::template::config::Config;
+use crate::template::history::{format_timestamp, History};
+use crate::template::readme_benchmarks::{get_path_for_bin, locate_table, Error};
+use crate::template::timings::Timings;
+use crate::template::Day;
+
+static MARKER: &str = "<!--- advent_readme_stars table --->";
+
+/// Solved parts of a day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion {
+    pub day: Day,
+    pub part_1: Option<Star>,
+    pub part_2: Option<Star>,
+}
+
+/// A solved part. Stars imported from an earlier table have no date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Star {
+    /// Unix timestamp of the first recorded run of the part.
+    pub solved_at: Option<u64>,
+}
+
+/// Completion record of every day with at least one star, sorted by day.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stars {
+    pub data: Vec<Completion>,
+}
+
+impl Completion {
+    fn part_mut(&mut self, part: u8) -> &mut Option<Star> {
+        if part == 1 {
+            &mut self.part_1
+        } else {
+            &mut self.part_2
+        }
+    }
+
+    /// When the first star of the day was solved, if known.
+    fn first_solved_at(&self) -> Option<u64> {
+        [self.part_1, self.part_2].into_iter().flatten().filter_map(|star| star.solved_at).min()
+    }
+}
+
+impl Stars {
+    /// Dehydrate the record to a JSON file.
+    pub fn store_file(&self) -> Result<(), io::Error> {
+        fs::write(Config::get().paths.stars(), JsonValue::from(self).stringify().unwrap_or_default())
+    }
+
+    /// Rehydrate the record from its JSON file. Returns `None` if it was never stored.
+    pub fn read_from_file() -> Option<Self> {
+        let path = Config::get().paths.stars();
+        let s = fs::read_to_string(&path).ok()?;
+        match Stars::try_from(s) {
+            Ok(stars) => Some(stars),
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Stars of an existing readme table, so that days solved before the record existed are kept.
+    pub fn from_readme(readme: &str) -> Self {
+        let Ok(position) = locate_table(readme, MARKER) else {
+            return Stars::default();
+        };
+        let mut stars = Stars::default();
+        for line in readme[position.pos_start..position.pos_end].lines() {
+            let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+            let Some(day) = cells
+                .get(1)
+                .and_then(|cell| cell.strip_prefix("[Day "))
+                .and_then(|cell| cell.split(']').next())
+                .and_then(|day| day.parse().ok())
+                .and_then(Day::new)
+            else {
+                continue;
+            };
+            let star = |index: usize| cells.get(index).filter(|cell| cell.contains('⭐')).map(|_| Star { solved_at: None });
+            stars.insert(Completion { day, part_1: star(2), part_2: star(3) });
+        }
+        stars
+    }
+
+    /// Adds the parts with a timing, for the days that still have a solution in `src/bin`.
+    /// New stars are dated with the first run of the part in `history`. Stars are never removed.
+    pub fn record(&mut self, timings: &Timings, history: &History, has_solution: impl Fn(Day) -> bool) {
+        for timing in timings.data.iter().filter(|timing| has_solution(timing.day)) {
+            for (part, solved) in [(1, &timing.part_1), (2, &timing.part_2)] {
+                if solved.is_none() {
+                    continue;
+                }
+                let solved_at = history
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.day == timing.day && entry.part_nanos(part).is_some())
+                    .map(|entry| entry.run.timestamp)
+                    .min();
+                let completion = self.completion_mut(timing.day);
+                completion.part_mut(part).get_or_insert(Star { solved_at });
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.data.iter().map(|c| usize::from(c.part_1.is_some()) + usize::from(c.part_2.is_some())).sum()
+    }
+
+    fn completion_mut(&mut self, day: Day) -> &mut Completion {
+        let index = match self.data.binary_search_by_key(&day, |c| c.day) {
+            Ok(index) => index,
+            Err(index) => {
+                self.data.insert(index, Completion { day, part_1: None, part_2: None });
+                index
+            }
+        };
+        &mut self.data[index]
+    }
+
+    fn insert(&mut self, completion: Completion) {
+        if completion.part_1.is_some() || completion.part_2.is_some() {
+            let day = completion.day;
+            *self.completion_mut(day) = completion;
+        }
+    }
+}
+
+/// Markdown table of `stars` under a `## {year} Results` heading, without the README markers.
+pub fn construct_table(year: u16, stars: &Stars, solved_at: bool, has_solution: impl Fn(Day) -> bool) -> String {
+    let star = |star: Option<Star>| if star.is_some() { "⭐" } else { " " };
+
+    let mut lines: Vec<String> = vec![
+        format!("## {year} Results"),
+        String::new(),
+        if solved_at {
+            "| Day | Part 1 | Part 2 | Solution | First solved |".into()
+        } else {
+            "| Day | Part 1 | Part 2 | Solution |".into()
+        },
+        if solved_at {
+            "| :---: | :---: | :---: | :---: | :---: |".into()
+        } else {
+            "| :---: | :---: | :---: | :---: |".into()
+        },
+    ];
+
+    for completion in &stars.data {
+        let day = completion.day;
+        let solution = if has_solution(day) {
+            format!("[{day}.rs]({})", get_path_for_bin(day))
+        } else {
+            "-".into()
+        };
+        let first_solved = if solved_at {
+            format!(" {} |", completion.first_solved_at().map_or_else(|| "-".into(), format_timestamp))
+        } else {
+            String::new()
+        };
+        lines.push(format!(
+            "| [Day {0}](https://adventofcode.com/{year}/day/{0}) | {1} | {2} | {solution} |{first_solved}",
+            day.into_inner(),
+            star(completion.part_1),
+            star(completion.part_2),
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// The configured year, or the one in the heading of the existing table.
+fn year(readme: &str) -> Result<u16, Error> {
+    Config::get()
+        .year
+        .or_else(|| {
+            let position = locate_table(readme, MARKER).ok()?;
+            readme[position.pos_start..position.pos_end]
+                .lines()
+                .find_map(|line| line.strip_prefix("## ")?.strip_suffix(" Results")?.parse().ok())
+        })
+        .ok_or_else(|| Error::Parser("Could not tell the year of the puzzles, please set `AOC_YEAR`.".into()))
+}
+
+fn update_content(s: &mut String, year: u16, stars: &Stars, solved_at: bool, has_solution: impl Fn(Day) -> bool) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = format!("{MARKER}\n{}\n{MARKER}", construct_table(year, stars, solved_at, has_solution));
+    s.replace_range(positions.pos_start..positions.pos_end, &table);
+    Ok(())
+}
+
+/// Records the parts solved since the last update and rewrites the stars table. Returns the record.
+pub fn update(solved_at: bool) -> Result<Stars, Error> {
+    let path = &Config::get().stars_readme;
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    let year = year(&readme)?;
+    let has_solution = |day: Day| Path::new(&get_path_for_bin(day)).exists();
+
+    let mut stars = Stars::read_from_file().unwrap_or_else(|| Stars::from_readme(&readme));
+    stars.record(&Timings::read_from_file(), &History::read_from_file(), has_solution);
+    stars.store_file()?;
+
+    update_content(&mut readme, year, &stars, solved_at, has_solution)?;
+    fs::write(path, &readme)?;
+    Ok(stars)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Stars> for JsonValue {
+    fn from(value: &Stars) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Stars {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Stars {
+            data: json_data
+                .iter()
+                .map(Completion::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Completion> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Completion) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        let star = |star: Option<Star>| {
+            star.map_or(JsonValue::Null, |star| {
+                let solved_at = star.solved_at.map_or(JsonValue::Null, |t| JsonValue::Number(t as f64));
+                JsonValue::Object(HashMap::from([("solved_at".to_string(), solved_at)]))
+            })
+        };
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part_1".into(), star(value.part_1));
+        map.insert("part_2".into(), star(value.part_2));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Completion {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected completion to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| day.parse().ok())
+            .ok_or("Expected completion.day to be a valid day.")?;
+
+        let star = |key: &str| -> Result<Option<Star>, String> {
+            match json.get(key) {
+                None | Some(JsonValue::Null) => Ok(None),
+                Some(JsonValue::Object(star)) => Ok(Some(Star {
+                    solved_at: star.get("solved_at").and_then(|v| v.get::<f64>()).map(|t| *t as u64),
+                })),
+                Some(_) => Err(format!("Expected completion.{key} to be an object or null.")),
+            }
+        };
+
+        Ok(Completion { day, part_1: star("part_1")?, part_2: star("part_2")? })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+    use crate::template::history::{HistoryEntry, RunInfo};
+    use crate::template::timings::Timing;
+
+    fn readme() -> String {
+        format!(
+            "foo\n{MARKER}\n## 2023 Results\n\n| Day | Part 1 | Part 2 |\n| :---: | :---: | :---: |\n| [Day 1](https://adventofcode.com/2023/day/1) | ⭐ | ⭐ |\n| [Day 11](https://adventofcode.com/2023/day/11) | ⭐ |   |\n{MARKER}\nbar"
+        )
+    }
+
+    fn timing(day: Day, part_2: Option<&str>) -> Timing {
+        Timing {
+            day,
+            part_1: Some("1.0ms".into()),
+            part_2: part_2.map(Into::into),
+            total_nanos: 1e6,
+            memory_1: None,
+            memory_2: None,
+            stats_1: None,
+            stats_2: None,
+            read_input: None,
+            parse: None,
+        }
+    }
+
+    fn entry(day: Day, timestamp: u64) -> HistoryEntry {
+        HistoryEntry {
+            run: RunInfo { timestamp, commit: None, host: None },
+            day,
+            part_1_nanos: Some(1e6),
+            part_2_nanos: Some(2e6),
+        }
+    }
+
+    #[test]
+    fn imports_existing_table() {
+        let stars = Stars::from_readme(&readme());
+        assert_eq!(stars.count(), 3);
+        assert_eq!(stars.data[1], Completion { day: day!(11), part_1: Some(Star { solved_at: None }), part_2: None });
+        assert_eq!(year(&readme()).ok(), Config::get().year.or(Some(2023)));
+    }
+
+    #[test]
+    fn records_new_stars_only() {
+        let mut stars = Stars::from_readme(&readme());
+        let timings = Timings { data: vec![timing(day!(11), Some("2.0ms")), timing(day!(12), None), timing(day!(13), Some("2.0ms"))] };
+        let history = History { entries: vec![entry(day!(11), 1_703_226_600), entry(day!(11), 1_703_000_000)] };
+        stars.record(&timings, &history, |day| day != day!(13));
+
+        assert_eq!(stars.count(), 5);
+        assert_eq!(stars.data[1].part_1, Some(Star { solved_at: None }));
+        assert_eq!(stars.data[1].part_2, Some(Star { solved_at: Some(1_703_000_000) }));
+        assert_eq!(stars.data[2], Completion { day: day!(12), part_1: Some(Star { solved_at: None }), part_2: None });
+    }
+
+    #[test]
+    fn updates_table() {
+        let mut stars = Stars::from_readme(&readme());
+        stars.data[1].part_2 = Some(Star { solved_at: Some(1_703_226_600) });
+        let mut s = readme();
+        update_content(&mut s, 2023, &stars, true, |day| day == day!(11)).unwrap();
+        assert!(s.starts_with("foo\n") && s.ends_with("\nbar"));
+        assert_eq!(s.matches(MARKER).count(), 2);
+        assert!(s.contains("| [Day 1](https://adventofcode.com/2023/day/1) | ⭐ | ⭐ | - | - |"));
+        assert!(s.contains("| [Day 11](https://adventofcode.com/2023/day/11) | ⭐ | ⭐ | [11.rs](./src/bin/11.rs) | 2023-12-22 06:30 |"));
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let mut stars = Stars::from_readme(&readme());
+        stars.data[1].part_2 = Some(Star { solved_at: Some(1_703_226_600) });
+        let json = JsonValue::from(&stars).stringify().unwrap();
+        assert_eq!(Stars::try_from(json), Ok(stars));
+    }
+}
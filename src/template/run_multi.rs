@@ -7,7 +7,14 @@ use super::{
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Runs the solutions of `days_to_run`. With `is_memory`, each day is run a second time in the
+/// `dhat` profile to record its heap usage, so that the profiler doesn't slow down the benchmark.
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    is_memory: bool,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     all_days().for_each(|day| {
@@ -28,7 +35,13 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
         if output.is_empty() {
             println!("Not solved.");
         } else {
-            let val = child_commands::parse_exec_time(&output, day);
+            let mut val = child_commands::parse_exec_time(&output, day);
+            if is_memory {
+                match child_commands::run_memory_profile(day) {
+                    Ok(output) => child_commands::parse_memory(&output, &mut val),
+                    Err(e) => eprintln!("Failed to profile the memory of day {day}: {e}"),
+                }
+            }
             timings.push(val);
         }
     });
@@ -51,6 +64,15 @@ pub enum Error {
     IO(io::Error),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::BrokenPipe => write!(f, "could not read output of child process."),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IO(e)
@@ -66,6 +88,7 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
+    use crate::template::timings::{Memory, PartStats};
     use crate::template::Day;
     use std::{
         io::{BufRead, BufReader},
@@ -94,11 +117,30 @@ pub mod child_commands {
             args.push("--time");
         }
 
-        // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        run_cargo(&args, |_| true)
+    }
+
+    /// Run the solution bin for a given day with dhat enabled, only forwarding the heap usage lines.
+    pub fn run_memory_profile(day: Day) -> Result<Vec<String>, Error> {
+        let day_padded = day.to_string();
+        let args = [
+            "run",
+            "--quiet",
+            "--profile",
+            "dhat",
+            "--features",
+            "dhat-heap",
+            "--bin",
+            &day_padded,
+        ];
+        run_cargo(&args, |line| Memory::parse_line(line).is_some())
+    }
 
+    /// Spawn cargo with piped stdout/stderr.
+    /// Stdout lines accepted by `echo` are forwarded to stdout, all are returned.
+    fn run_cargo(args: &[&str], echo: impl Fn(&str) -> bool) -> Result<Vec<String>, Error> {
         let mut cmd = Command::new("cargo")
-            .args(&args)
+            .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
@@ -116,7 +158,9 @@ pub mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
+            if echo(&line) {
+                println!("{line}");
+            }
             output.push(line);
         }
 
@@ -132,6 +176,12 @@ pub mod child_commands {
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            memory_1: None,
+            memory_2: None,
+            stats_1: None,
+            stats_2: None,
+            read_input: None,
+            parse: None,
         };
 
         output
@@ -147,13 +197,22 @@ pub mod child_commands {
                 };
 
                 let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                Some((part, timing_str, nanos, parse_stats(l, nanos)))
             })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
+            .for_each(|(part, timing_str, nanos, stats)| {
+                // reading the input isn't part of the solution, so it stays out of the total.
+                if part == "Read input" {
+                    timings.read_input = Some(timing_str.into());
+                    return;
+                }
+                if part == "Parse" {
+                    timings.parse = Some(timing_str.into());
+                } else if part.contains("Part 1") {
                     timings.part_1 = Some(timing_str.into());
+                    timings.stats_1 = stats;
                 } else if part.contains("Part 2") {
                     timings.part_2 = Some(timing_str.into());
+                    timings.stats_2 = stats;
                 }
 
                 timings.total_nanos += nanos;
@@ -162,6 +221,16 @@ pub mod child_commands {
         timings
     }
 
+    pub fn parse_memory(output: &[String], timing: &mut super::Timing) {
+        for (part, memory) in output.iter().filter_map(|l| Memory::parse_line(l)) {
+            match part {
+                1 => timing.memory_1 = Some(memory),
+                2 => timing.memory_2 = Some(memory),
+                _ => {}
+            }
+        }
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -172,19 +241,42 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
+            .split('±')
+            .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
+        Some((str_timing, parse_duration(str_timing)?))
+    }
+
+    /// Spread of the samples, e.g. `(1.2ms ± 3.4µs @ 100 samples)`.
+    /// Output of runners that don't print the standard deviation has none.
+    fn parse_stats(line: &str, mean_nanos: f64) -> Option<PartStats> {
+        let (durations, samples) = line
+            .split(" samples)")
+            .next()?
+            .split('(')
+            .next_back()?
+            .split_once('@')?;
+        let std_dev = durations.split_once('±')?.1.trim();
+
+        Some(PartStats {
+            mean_nanos,
+            std_dev_nanos: parse_duration(std_dev)?,
+            samples: samples.trim().parse().ok()?,
+        })
+    }
+
+    /// Nanoseconds of a duration formatted with `{:?}`, e.g. `74.13ms`.
+    pub fn parse_duration(s: &str) -> Option<f64> {
+        match s {
             s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
             s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
             s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
             s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        }
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -203,7 +295,7 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_memory};
 
         use crate::day;
 
@@ -220,6 +312,51 @@ pub mod child_commands {
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
             assert_eq!(res.part_1.unwrap(), "74.13ns");
             assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_eq!(res.stats_1, None);
+        }
+
+        #[test]
+        fn parses_standard_deviations() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (1.5µs ± 200.0ns @ 1000 samples)".into(),
+                    "Part 2: 10 (2.0ms ± 1.0µs @ 500 samples)".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 2001500_f64);
+            assert_eq!(res.part_1.unwrap(), "1.5µs");
+            let stats = res.stats_2.unwrap();
+            assert_approx_eq!(stats.mean_nanos, 2_000_000_f64);
+            assert_approx_eq!(stats.std_dev_nanos, 1000_f64);
+            assert_eq!(stats.samples, 500);
+        }
+
+        #[test]
+        fn parses_parse_time_out_of_total() {
+            let res = parse_exec_time(
+                &[
+                    "Read input: (12.0µs ± 1.0µs @ 10000 samples)".into(),
+                    "Part 1: 0 (1.5µs ± 200.0ns @ 1000 samples)".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 1500_f64);
+            assert_eq!(res.read_input.as_deref(), Some("12.0µs"));
+            assert_eq!(res.part_1.as_deref(), Some("1.5µs"));
+        }
+
+        #[test]
+        fn parses_shared_parse_into_total() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: (500.0ns ± 20.0ns @ 10000 samples)".into(),
+                    "Part 1: 0 (1.5µs ± 200.0ns @ 1000 samples)".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 2000_f64);
+            assert_eq!(res.parse.as_deref(), Some("500.0ns"));
         }
 
         #[test]
@@ -251,5 +388,21 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_memory() {
+            let mut res = parse_exec_time(&["Part 1: 0 (74.13ns @ 100000 samples)".into()], day!(1));
+            parse_memory(
+                &[
+                    "Part 1: 0 (1.2µs)".into(),
+                    "Part 1 heap: 2048 bytes peak, 12 allocations totalling 4096 bytes".into(),
+                    "Part 2: ✖        ".into(),
+                ],
+                &mut res,
+            );
+            let memory = res.memory_1.unwrap();
+            assert_eq!((memory.peak_bytes, memory.allocations, memory.total_bytes), (2048, 12, 4096));
+            assert_eq!(res.memory_2, None);
+        }
     }
 }
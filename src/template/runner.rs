@@ -4,40 +4,142 @@ use std::hint::black_box;
 use std::io::{stdout, Write};
 use std::process::Output;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{env, process};
 
+use crate::template::answers;
+use crate::template::config::Config;
+use crate::template::timings::Memory;
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Values a solution part may return: an `Option`, or a `Result` whose error gets reported.
+pub trait Answer {
+    type Value: Display;
+
+    /// The answer, or the error to report when there is none.
+    fn into_result(self) -> Result<Self::Value, Option<String>>;
+}
+
+impl<T: Display> Answer for Option<T> {
+    type Value = T;
+
+    fn into_result(self) -> Result<T, Option<String>> {
+        self.ok_or(None)
+    }
+}
+
+impl<T: Display, E: Display> Answer for Result<T, E> {
+    type Value = T;
+
+    fn into_result(self) -> Result<T, Option<String>> {
+        self.map_err(|e| Some(e.to_string()))
+    }
+}
+
+pub fn run_part<I: Clone, A: Answer>(func: impl Fn(I) -> A, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, std_dev, samples, memory) = run_timed(
+        |input| func(input).into_result(),
+        input,
+        |result| print_result(&result.as_ref().ok(), &part_str, ""),
+    );
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(&result.as_ref().ok(), &part_str, &format_duration(&duration, &std_dev, samples));
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    if let Some(memory) = memory {
+        println!("{part_str} heap: {memory}");
+    }
+
+    match result {
+        Ok(result) => {
+            if env::args().any(|x| x == "--record") {
+                answers::record(day, part, &result.to_string());
+            }
+            submit_result(result, day, part);
+        }
+        Err(Some(e)) => eprintln!("{e}"),
+        Err(None) => {}
     }
 }
 
+/// Read the input of a day with `reader`, exiting if it can't be read. With `--time`, reading is
+/// benched like the parts and reported on its own line, outside the total of the day.
+pub fn read_input<E: Display>(reader: impl Fn() -> Result<String, E>) -> String {
+    let timer = Instant::now();
+    let input = reader().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+    let base_time = timer.elapsed();
+
+    if env::args().any(|x| x == "--time") {
+        print!("Read input:");
+        let (duration, std_dev, samples) = bench(|()| reader().is_ok(), (), &base_time);
+        print!("\r");
+        println!("Read input:{}", format_duration(&duration, &std_dev, samples));
+    }
+
+    input
+}
+
+/// Parse the input of a day once for both parts with `parser`, exiting if the input is invalid.
+/// Parsing is timed like the parts and reported on its own line, counted in the total of the day.
+pub fn parse_input<T, E: Display>(parser: impl Fn(&str) -> Result<T, E>, input: &str) -> T {
+    let timer = Instant::now();
+    let parsed = parser(input).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+    let base_time = timer.elapsed();
+
+    let (duration, std_dev, samples) = if env::args().any(|x| x == "--time") {
+        print!("Parse:");
+        let run = bench(|input| parser(input).is_ok(), input, &base_time);
+        print!("\r");
+        run
+    } else {
+        (base_time, Duration::ZERO, 1)
+    };
+    println!("Parse:{}", format_duration(&duration, &std_dev, samples));
+
+    parsed
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer,
+///     see the `benchmarks` budget of `aoc.toml`.)
+///     The standard deviation of the samples is returned along with their average.
+///
+/// With the `dhat-heap` feature, the heap usage of the first execution is returned as well.
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Duration, Duration, u128, Option<Memory>) {
     let timer = Instant::now();
-    let result = {
+    let (result, memory) = {
         let input = input.clone();
 
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        let result = func(input);
+
+        #[cfg(feature = "dhat-heap")]
+        let memory = {
+            let stats = dhat::HeapStats::get();
+            Some(Memory {
+                peak_bytes: stats.max_bytes as u64,
+                total_bytes: stats.total_bytes,
+                allocations: stats.total_blocks,
+            })
+        };
+        #[cfg(not(feature = "dhat-heap"))]
+        let memory = None;
+
+        (result, memory)
     };
     let base_time = timer.elapsed();
 
@@ -46,25 +148,23 @@ fn run_timed<I: Clone, T>(
     let run = if std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
-        (base_time, 1)
+        (base_time, Duration::ZERO, 1)
     };
 
-    (result, run.0, run.1)
+    (result, run.0, run.1, run.2, memory)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+) -> (Duration, Duration, u128) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_iterations = Config::get().budget.samples(*base_time);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -76,9 +176,12 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
+    let average = average_duration(&timers);
+
     (
         #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
+        Duration::from_nanos(average as u64),
+        Duration::from_nanos(std_deviation(&timers, average)),
         bench_iterations,
     )
 }
@@ -91,11 +194,24 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
+/// Sample standard deviation, in nanoseconds.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn std_deviation(numbers: &[Duration], average: u128) -> u64 {
+    if numbers.len() < 2 {
+        return 0;
+    }
+    let squares: f64 = numbers
+        .iter()
+        .map(|d| (d.as_nanos() as f64 - average as f64).powi(2))
+        .sum();
+    (squares / (numbers.len() - 1) as f64).sqrt() as u64
+}
+
+fn format_duration(duration: &Duration, std_dev: &Duration, samples: u128) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
     } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+        format!(" ({duration:.1?} ± {std_dev:.1?} @ {samples} samples)")
     }
 }
 
@@ -0,0 +1,294 @@
+/// Progress of every day for `cargo status`: what is scaffolded, downloaded, tested, answered and benchmarked.
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use tinyjson::JsonValue;
+
+use crate::template::answers::Answers;
+use crate::template::config::Config;
+use crate::template::history::{format_timestamp, History};
+use crate::template::readme_benchmarks::get_path_for_bin;
+use crate::template::timings::Timings;
+use crate::template::watch::Tests;
+use crate::template::{all_days, Day};
+
+/// Longest answer shown in the table, longer ones are cut.
+const MAX_ANSWER_LENGTH: usize = 16;
+
+/// Latest benchmark of a day, from `timings.json` and the date of its run in the history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Benchmark {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+    pub total_nanos: f64,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayStatus {
+    pub day: Day,
+    /// Whether `src/bin/DD.rs` exists.
+    pub scaffolded: bool,
+    /// Whether the input was downloaded and isn't empty.
+    pub input: bool,
+    /// Whether an example, shared or for a single part, isn't empty.
+    pub example: bool,
+    /// `None` when the tests weren't run, e.g. for a day without a solution.
+    pub tests: Option<Tests>,
+    pub answer_1: Option<String>,
+    pub answer_2: Option<String>,
+    pub benchmark: Option<Benchmark>,
+}
+
+/// Status of every day from the files on disk, without running the tests.
+pub fn collect() -> Vec<DayStatus> {
+    let answers = Answers::read_from_file();
+    let timings = if Config::get().paths.timings().exists() { Timings::read_from_file() } else { Timings::default() };
+    let history = History::read_from_file();
+    all_days().map(|day| day_status(day, &answers, &timings, &history)).collect()
+}
+
+fn day_status(day: Day, answers: &Answers, timings: &Timings, history: &History) -> DayStatus {
+    let paths = &Config::get().paths;
+    let example = [format!("{day}.txt"), format!("{day}-1.txt"), format!("{day}-2.txt")]
+        .iter()
+        .any(|name| is_non_empty(&paths.examples.join(name)));
+    let benchmark = timings.data.iter().find(|t| t.day == day).map(|timing| Benchmark {
+        part_1: timing.part_1.clone(),
+        part_2: timing.part_2.clone(),
+        total_nanos: timing.total_nanos,
+        timestamp: history.entries.iter().rev().find(|e| e.day == day).map(|e| e.run.timestamp),
+    });
+    DayStatus {
+        day,
+        scaffolded: Path::new(&get_path_for_bin(day)).exists(),
+        input: is_non_empty(&paths.inputs.join(format!("{day}.txt"))),
+        example,
+        tests: None,
+        answer_1: answers.get(day, 1).map(String::from),
+        answer_2: answers.get(day, 2).map(String::from),
+        benchmark,
+    }
+}
+
+fn is_non_empty(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Aligned table of `statuses`, one row per day, followed by a summary line.
+pub fn table(statuses: &[DayStatus]) -> String {
+    let mut rows: Vec<[String; 8]> = vec![[
+        "Day", "Bin", "Input", "Example", "Tests", "Part 1", "Part 2", "Last benchmark",
+    ]
+    .map(String::from)];
+    for status in statuses {
+        rows.push([
+            status.day.to_string(),
+            mark(status.scaffolded).into(),
+            mark(status.input).into(),
+            mark(status.example).into(),
+            status.tests.as_ref().map_or("-".into(), tests_cell),
+            answer_cell(status.answer_1.as_deref()),
+            answer_cell(status.answer_2.as_deref()),
+            status.benchmark.as_ref().map_or("-".into(), benchmark_cell),
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..8).map(|i| rows.iter().map(|row| row[i].chars().count()).max().unwrap_or(0)).collect();
+    let mut lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{cell:<width$}")).collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect();
+
+    let scaffolded = statuses.iter().filter(|s| s.scaffolded).count();
+    let answers = statuses.iter().map(|s| usize::from(s.answer_1.is_some()) + usize::from(s.answer_2.is_some())).sum::<usize>();
+    let mut summary = format!("{scaffolded} day(s) scaffolded, {answers} answer(s) recorded");
+    let failing = statuses.iter().filter(|s| s.tests.as_ref().is_some_and(|t| !is_passing(t))).count();
+    if failing > 0 {
+        summary.push_str(&format!(", {failing} day(s) failing their tests"));
+    }
+    lines.push(String::new());
+    lines.push(format!("{summary}."));
+    lines.join("\n")
+}
+
+fn mark(value: bool) -> &'static str {
+    if value { "✔" } else { "✖" }
+}
+
+fn is_passing(tests: &Tests) -> bool {
+    matches!(tests, Tests::Ran { failures, .. } if failures.is_empty())
+}
+
+fn tests_cell(tests: &Tests) -> String {
+    match tests {
+        Tests::BuildFailed(_) => "✖ build failed".into(),
+        Tests::Ran { passed, failures } if failures.is_empty() => format!("✔ {passed} passed"),
+        Tests::Ran { passed, failures } => format!("✖ {} of {} failed", failures.len(), passed + failures.len()),
+    }
+}
+
+fn answer_cell(answer: Option<&str>) -> String {
+    let Some(answer) = answer else {
+        return "-".into();
+    };
+    let answer = answer.lines().next().unwrap_or_default();
+    if answer.chars().count() > MAX_ANSWER_LENGTH {
+        format!("{}…", answer.chars().take(MAX_ANSWER_LENGTH - 1).collect::<String>())
+    } else {
+        answer.to_string()
+    }
+}
+
+fn benchmark_cell(benchmark: &Benchmark) -> String {
+    let part = |time: &Option<String>| time.clone().unwrap_or_else(|| "-".into());
+    let mut cell = format!("{} / {}", part(&benchmark.part_1), part(&benchmark.part_2));
+    if let Some(timestamp) = benchmark.timestamp {
+        cell.push_str(&format!(" on {}", format_timestamp(timestamp)));
+    }
+    cell
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The statuses as `{"year": 2023, "days": [...]}`, for scripts and bots.
+pub fn to_json(year: Option<u16>, statuses: &[DayStatus]) -> JsonValue {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert("year".into(), year.map_or(JsonValue::Null, |year| JsonValue::Number(f64::from(year))));
+    map.insert("days".into(), JsonValue::Array(statuses.iter().map(JsonValue::from).collect()));
+
+    JsonValue::Object(map)
+}
+
+impl From<&DayStatus> for JsonValue {
+    fn from(value: &DayStatus) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        let string = |s: &Option<String>| s.clone().map_or(JsonValue::Null, JsonValue::String);
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("scaffolded".into(), JsonValue::Boolean(value.scaffolded));
+        map.insert("input".into(), JsonValue::Boolean(value.input));
+        map.insert("example".into(), JsonValue::Boolean(value.example));
+        map.insert("tests".into(), value.tests.as_ref().map_or(JsonValue::Null, JsonValue::from));
+        map.insert("answer_1".into(), string(&value.answer_1));
+        map.insert("answer_2".into(), string(&value.answer_2));
+        map.insert(
+            "benchmark".into(),
+            value.benchmark.as_ref().map_or(JsonValue::Null, |benchmark| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                map.insert("part_1".into(), string(&benchmark.part_1));
+                map.insert("part_2".into(), string(&benchmark.part_2));
+                map.insert("total_nanos".into(), JsonValue::Number(benchmark.total_nanos));
+                #[allow(clippy::cast_precision_loss)]
+                let timestamp = benchmark.timestamp.map_or(JsonValue::Null, |t| JsonValue::Number(t as f64));
+                map.insert("timestamp".into(), timestamp);
+                JsonValue::Object(map)
+            }),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl From<&Tests> for JsonValue {
+    fn from(value: &Tests) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        let strings = |lines: &[String]| JsonValue::Array(lines.iter().cloned().map(JsonValue::String).collect());
+
+        match value {
+            Tests::BuildFailed(errors) => {
+                map.insert("status".into(), JsonValue::String("build_failed".into()));
+                map.insert("errors".into(), strings(errors));
+            }
+            Tests::Ran { passed, failures } => {
+                let status = if failures.is_empty() { "passed" } else { "failed" };
+                map.insert("status".into(), JsonValue::String(status.into()));
+                #[allow(clippy::cast_precision_loss)]
+                map.insert("passed".into(), JsonValue::Number(*passed as f64));
+                map.insert("failures".into(), strings(failures));
+            }
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    fn statuses() -> Vec<DayStatus> {
+        let empty = |day| DayStatus {
+            day,
+            scaffolded: false,
+            input: false,
+            example: false,
+            tests: None,
+            answer_1: None,
+            answer_2: None,
+            benchmark: None,
+        };
+        vec![
+            DayStatus {
+                scaffolded: true,
+                input: true,
+                example: true,
+                tests: Some(Tests::Ran { passed: 2, failures: vec![] }),
+                answer_1: Some("1320".into()),
+                answer_2: Some("a very long answer indeed".into()),
+                benchmark: Some(Benchmark {
+                    part_1: Some("2.5µs".into()),
+                    part_2: None,
+                    total_nanos: 2500.0,
+                    timestamp: Some(1_703_226_600),
+                }),
+                ..empty(day!(15))
+            },
+            DayStatus {
+                scaffolded: true,
+                tests: Some(Tests::Ran { passed: 1, failures: vec!["tests::test_part_two".into()] }),
+                ..empty(day!(16))
+            },
+            empty(day!(17)),
+        ]
+    }
+
+    #[test]
+    fn writes_table() {
+        assert_eq!(
+            table(&statuses()),
+            [
+                "Day  Bin  Input  Example  Tests            Part 1  Part 2            Last benchmark",
+                "15   ✔    ✔      ✔        ✔ 2 passed       1320    a very long ans…  2.5µs / - on 2023-12-22 06:30",
+                "16   ✔    ✖      ✖        ✖ 1 of 2 failed  -       -                 -",
+                "17   ✖    ✖      ✖        -                -       -                 -",
+                "",
+                "2 day(s) scaffolded, 2 answer(s) recorded, 1 day(s) failing their tests.",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn writes_json() {
+        let json = to_json(Some(2023), &statuses()[..2]);
+        let days: &Vec<JsonValue> = json["days"].get().unwrap();
+        assert_eq!(json["year"], JsonValue::Number(2023.0));
+        assert_eq!(days[0]["day"], JsonValue::String("15".into()));
+        assert_eq!(days[0]["input"], JsonValue::Boolean(true));
+        assert_eq!(days[0]["tests"]["status"], JsonValue::String("passed".into()));
+        assert_eq!(days[0]["benchmark"]["timestamp"], JsonValue::Number(1_703_226_600.0));
+        assert_eq!(days[0]["benchmark"]["part_2"], JsonValue::Null);
+        assert_eq!(days[1]["tests"]["status"], JsonValue::String("failed".into()));
+        assert_eq!(days[1]["answer_1"], JsonValue::Null);
+        assert_eq!(days[1]["benchmark"], JsonValue::Null);
+    }
+}
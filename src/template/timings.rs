@@ -1,9 +1,12 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::path::PathBuf;
+use std::{collections::HashMap, fmt::Display, fs, io::Error, io::ErrorKind, str::FromStr};
 use tinyjson::JsonValue;
 
+use crate::template::config::Config;
+use crate::template::history::{format_nanos, RunInfo};
+use crate::template::run_multi::child_commands::parse_duration;
 use crate::template::Day;
 
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -12,6 +15,73 @@ pub struct Timing {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    /// Heap usage of each part, measured with `time --memory`.
+    pub memory_1: Option<Memory>,
+    pub memory_2: Option<Memory>,
+    /// Spread of the benchmark samples of each part.
+    pub stats_1: Option<PartStats>,
+    pub stats_2: Option<PartStats>,
+    /// Time to read the input file, see [`crate::template::runner::read_input`].
+    pub read_input: Option<String>,
+    /// Time of the parse shared by both parts, see [`crate::template::runner::parse_input`].
+    /// Unlike reading the input, it counts in `total_nanos`.
+    pub parse: Option<String>,
+}
+
+/// Mean and standard deviation of the benchmark samples of a part.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PartStats {
+    pub mean_nanos: f64,
+    pub std_dev_nanos: f64,
+    pub samples: u64,
+}
+
+/// Heap usage of a single run of a solution part, as reported by dhat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Memory {
+    pub peak_bytes: u64,
+    pub total_bytes: u64,
+    pub allocations: u64,
+}
+
+impl Memory {
+    /// Parses the line printed by the runner, e.g. `Part 1 heap: 2048 bytes peak, 12 allocations totalling 4096 bytes`.
+    pub fn parse_line(line: &str) -> Option<(u8, Self)> {
+        let (part, stats) = line.split_once(" heap:")?;
+        let part = part.strip_prefix("Part ")?.parse().ok()?;
+        let numbers: Vec<u64> = stats.split_whitespace().filter_map(|x| x.parse().ok()).collect();
+        let [peak_bytes, allocations, total_bytes] = numbers[..] else {
+            return None;
+        };
+        Some((part, Memory { peak_bytes, total_bytes, allocations }))
+    }
+}
+
+impl Display for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} bytes peak, {} allocations totalling {} bytes",
+            self.peak_bytes, self.allocations, self.total_bytes
+        )
+    }
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 KiB`.
+#[must_use]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
 }
 
 /// Represents benchmark times for a set of days.
@@ -25,13 +95,13 @@ impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(Config::get().paths.timings())?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        let s = fs::read_to_string(TIMINGS_FILE_PATH)
+        let s = fs::read_to_string(Config::get().paths.timings())
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from);
 
@@ -45,11 +115,19 @@ impl Timings {
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
+    /// Memory stats are kept from `self` when `other` was run without `--memory`.
     pub fn merge(&self, new: &Self) -> Self {
         let mut data: Vec<Timing> = vec![];
 
         for timing in &new.data {
-            data.push(timing.clone());
+            let mut timing = timing.clone();
+            if timing.memory_1.is_none() && timing.memory_2.is_none() {
+                if let Some(stored) = self.data.iter().find(|t| t.day == timing.day) {
+                    timing.memory_1 = stored.memory_1;
+                    timing.memory_2 = stored.memory_2;
+                }
+            }
+            data.push(timing);
         }
 
         for timing in &self.data {
@@ -58,7 +136,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -67,6 +145,13 @@ impl Timings {
         self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
     }
 
+    /// Whether any day was run with `--memory`.
+    pub fn has_memory(&self) -> bool {
+        self.data
+            .iter()
+            .any(|t| t.memory_1.is_some() || t.memory_2.is_some())
+    }
+
     pub fn is_day_complete(&self, day: Day) -> bool {
         self.data
             .iter()
@@ -74,6 +159,186 @@ impl Timings {
     }
 }
 
+impl Timing {
+    /// Benchmark of `part`. Timings stored without a standard deviation count as a single sample.
+    pub fn part_stats(&self, part: u8) -> Option<PartStats> {
+        let (time, stats) = match part {
+            1 => (&self.part_1, self.stats_1),
+            2 => (&self.part_2, self.stats_2),
+            _ => return None,
+        };
+        stats.or_else(|| {
+            let mean_nanos = parse_duration(time.as_deref()?)?;
+            Some(PartStats { mean_nanos, std_dev_nanos: 0.0, samples: 1 })
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Timings saved under a name with `time --save-baseline`, to compare later runs against.
+#[derive(Clone, Debug)]
+pub struct Baseline {
+    pub name: String,
+    pub run: RunInfo,
+    pub timings: Timings,
+}
+
+/// Timing of one part in a baseline and in the current run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BaselineComparison {
+    pub day: Day,
+    pub part: u8,
+    pub baseline: PartStats,
+    pub current: PartStats,
+}
+
+impl Baseline {
+    fn path(name: &str) -> Result<PathBuf, Error> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !name.starts_with('.');
+        if !valid {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid baseline name `{name}`, use letters, digits, `-`, `_` and `.`."),
+            ));
+        }
+        Ok(Config::get().paths.baselines().join(format!("{name}.json")))
+    }
+
+    /// Store the baseline, replacing any baseline with the same name.
+    pub fn save(&self) -> Result<(), Error> {
+        let path = Self::path(&self.name)?;
+        fs::create_dir_all(Config::get().paths.baselines())?;
+        let json = JsonValue::from(self);
+        let mut file = fs::File::create(path)?;
+        json.format_to(&mut file)
+    }
+
+    pub fn load(name: &str) -> Result<Self, String> {
+        let path = Self::path(name).map_err(|e| e.to_string())?;
+        let s = fs::read_to_string(&path).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => format!("no baseline named `{name}`, see `cargo time --list-baselines`."),
+            _ => format!("could not read {}: {e}", path.display()),
+        })?;
+        Baseline::try_from(s)
+    }
+
+    /// All stored baselines, sorted by name.
+    pub fn list() -> Result<Vec<Self>, Error> {
+        let entries = match fs::read_dir(Config::get().paths.baselines()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let mut baselines = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(Baseline::try_from) {
+                Ok(baseline) => baselines.push(baseline),
+                Err(e) => eprintln!("{}: {e}", path.display()),
+            }
+        }
+        baselines.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        Ok(baselines)
+    }
+
+    pub fn delete(name: &str) -> Result<(), Error> {
+        fs::remove_file(Self::path(name)?)
+    }
+
+    /// Pairs the parts of `timings` with the same parts in the baseline.
+    pub fn compare(&self, timings: &Timings) -> Vec<BaselineComparison> {
+        let mut comparisons = vec![];
+        for timing in &timings.data {
+            let Some(saved) = self.timings.data.iter().find(|t| t.day == timing.day) else {
+                continue;
+            };
+            for part in [1, 2] {
+                if let (Some(baseline), Some(current)) = (saved.part_stats(part), timing.part_stats(part)) {
+                    if baseline.mean_nanos > 0.0 {
+                        comparisons.push(BaselineComparison { day: timing.day, part, baseline, current });
+                    }
+                }
+            }
+        }
+        comparisons
+    }
+}
+
+impl BaselineComparison {
+    /// Current time over baseline time, below 1 when the part got faster.
+    pub fn ratio(&self) -> f64 {
+        self.current.mean_nanos / self.baseline.mean_nanos
+    }
+
+    /// Welch's t statistic of the difference in means, `None` when neither side has a spread.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn t_statistic(&self) -> Option<f64> {
+        let variance = |stats: &PartStats| stats.std_dev_nanos.powi(2) / stats.samples.max(1) as f64;
+        let standard_error = (variance(&self.baseline) + variance(&self.current)).sqrt();
+        if standard_error == 0.0 {
+            return None;
+        }
+        Some((self.current.mean_nanos - self.baseline.mean_nanos) / standard_error)
+    }
+
+    /// Whether the difference is unlikely to be noise, at roughly 95% confidence.
+    /// `None` when the stored timings have no standard deviation.
+    pub fn is_significant(&self) -> Option<bool> {
+        self.t_statistic().map(|t| t.abs() > 1.96)
+    }
+
+    /// Whether the part got slower by more than `threshold` percent, unless the difference is
+    /// known to be within noise.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.ratio() > 1.0 + threshold / 100.0 && self.is_significant() != Some(false)
+    }
+}
+
+/// Side-by-side table of a baseline and the current run.
+pub fn format_comparison_table(comparisons: &[BaselineComparison]) -> String {
+    let format_stats = |stats: &PartStats| {
+        let mean = format_nanos(stats.mean_nanos);
+        if stats.samples > 1 {
+            format!("{mean} ± {}", format_nanos(stats.std_dev_nanos))
+        } else {
+            mean
+        }
+    };
+
+    let mut lines: Vec<String> = vec![
+        "| Day | Part | Baseline | Current | Ratio | Significant |".into(),
+        "| :---: | :---: | ---: | ---: | ---: | :---: |".into(),
+    ];
+
+    for comparison in comparisons {
+        let significant = match (comparison.is_significant(), comparison.t_statistic()) {
+            (Some(true), Some(t)) => format!("yes (t = {t:.1})"),
+            (Some(false), Some(t)) => format!("no (t = {t:.1})"),
+            _ => "?".into(),
+        };
+        lines.push(format!(
+            "| {} | {} | `{}` | `{}` | {:.2}x | {} |",
+            comparison.day.into_inner(),
+            comparison.part,
+            format_stats(&comparison.baseline),
+            format_stats(&comparison.current),
+            comparison.ratio(),
+            significant
+        ));
+    }
+
+    lines.join("\n")
+}
+
 /* -------------------------------------------------------------------------- */
 
 impl From<Timings> for JsonValue {
@@ -140,10 +405,90 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        map.insert("memory_1".into(), value.memory_1.map_or(JsonValue::Null, JsonValue::from));
+        map.insert("memory_2".into(), value.memory_2.map_or(JsonValue::Null, JsonValue::from));
+        map.insert("stats_1".into(), value.stats_1.map_or(JsonValue::Null, JsonValue::from));
+        map.insert("stats_2".into(), value.stats_2.map_or(JsonValue::Null, JsonValue::from));
+        map.insert("read_input".into(), value.read_input.clone().map_or(JsonValue::Null, JsonValue::String));
+        map.insert("parse".into(), value.parse.clone().map_or(JsonValue::Null, JsonValue::String));
+
         JsonValue::Object(map)
     }
 }
 
+impl From<Memory> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: Memory) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("peak_bytes".into(), JsonValue::Number(value.peak_bytes as f64));
+        map.insert("total_bytes".into(), JsonValue::Number(value.total_bytes as f64));
+        map.insert("allocations".into(), JsonValue::Number(value.allocations as f64));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Memory {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory to be a JSON object.")?;
+
+        let field = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .map(|v| v as u64)
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        Ok(Memory {
+            peak_bytes: field("peak_bytes")?,
+            total_bytes: field("total_bytes")?,
+            allocations: field("allocations")?,
+        })
+    }
+}
+
+impl From<PartStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: PartStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("mean_nanos".into(), JsonValue::Number(value.mean_nanos));
+        map.insert("std_dev_nanos".into(), JsonValue::Number(value.std_dev_nanos));
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stats to be a JSON object.")?;
+
+        let field = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected stats.{key} to be a number."))
+        };
+
+        Ok(PartStats {
+            mean_nanos: field("mean_nanos")?,
+            std_dev_nanos: field("std_dev_nanos")?,
+            samples: field("samples")? as u64,
+        })
+    }
+}
+
 impl TryFrom<&JsonValue> for Timing {
     type Error = String;
 
@@ -173,11 +518,74 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // absent from files written before memory stats, standard deviations and input read times were recorded.
+        let memory = |key: &str| match json.get(key) {
+            Some(v) if !v.is_null() => Memory::try_from(v).map(Some),
+            _ => Ok(None),
+        };
+        let stats = |key: &str| match json.get(key) {
+            Some(v) if !v.is_null() => PartStats::try_from(v).map(Some),
+            _ => Ok(None),
+        };
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            memory_1: memory("memory_1")?,
+            memory_2: memory("memory_2")?,
+            stats_1: stats("stats_1")?,
+            stats_2: stats("stats_2")?,
+            read_input: json.get("read_input").and_then(|v| v.get::<String>()).cloned(),
+            parse: json.get("parse").and_then(|v| v.get::<String>()).cloned(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Baseline> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Baseline) -> Self {
+        let mut map = match JsonValue::from(value.timings.clone()) {
+            JsonValue::Object(map) => map,
+            _ => unreachable!("timings are serialized to an object"),
+        };
+
+        let optional = |s: &Option<String>| s.clone().map_or(JsonValue::Null, JsonValue::String);
+        map.insert("name".into(), JsonValue::String(value.name.clone()));
+        map.insert("timestamp".into(), JsonValue::Number(value.run.timestamp as f64));
+        map.insert("commit".into(), optional(&value.run.commit));
+        map.insert("host".into(), optional(&value.run.host));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Baseline {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+        let map = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let string = |key: &str| map.get(key).and_then(|v| v.get::<String>()).cloned();
+
+        Ok(Baseline {
+            name: string("name").ok_or("expected baseline to have a `name`.")?,
+            run: RunInfo {
+                timestamp: map
+                    .get("timestamp")
+                    .and_then(|v| v.get::<f64>())
+                    .map_or(0, |t| *t as u64),
+                commit: string("commit"),
+                host: string("host"),
+            },
+            timings: Timings::try_from(value)?,
         })
     }
 }
@@ -198,18 +606,36 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 },
             ],
         }
@@ -230,6 +656,16 @@ mod tests {
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_memory_stats() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000, "memory_1": { "peak_bytes": 2048, "total_bytes": 4096, "allocations": 12 }, "memory_2": null }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.memory_1.map(|m| (m.peak_bytes, m.total_bytes, m.allocations)), Some((2048, 4096, 12)));
+            assert!(timing.memory_2.is_none());
+            assert!(timings.has_memory());
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -275,6 +711,104 @@ mod tests {
         }
     }
 
+    mod memory {
+        use crate::template::timings::{format_bytes, Memory};
+
+        #[test]
+        fn parses_runner_line() {
+            let memory = Memory { peak_bytes: 2048, total_bytes: 4096, allocations: 12 };
+            assert_eq!(Memory::parse_line(&format!("Part 2 heap: {memory}")), Some((2, memory)));
+            assert_eq!(Memory::parse_line("Part 2: 42 (1.0ms)"), None);
+        }
+
+        #[test]
+        fn formats_bytes() {
+            assert_eq!(format_bytes(512), "512 B");
+            assert_eq!(format_bytes(1536), "1.5 KiB");
+            assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+        }
+    }
+
+    mod baselines {
+        use crate::{
+            day,
+            template::history::RunInfo,
+            template::timings::{format_comparison_table, Baseline, PartStats, Timing, Timings},
+        };
+
+        fn timing(part_1: &str, stats_1: Option<PartStats>) -> Timing {
+            Timing {
+                day: day!(22),
+                part_1: Some(part_1.into()),
+                part_2: None,
+                total_nanos: 0_f64,
+                memory_1: None,
+                memory_2: None,
+                stats_1,
+                stats_2: None,
+                read_input: None,
+                parse: None,
+            }
+        }
+
+        fn stats(mean_nanos: f64, std_dev_nanos: f64) -> Option<PartStats> {
+            Some(PartStats { mean_nanos, std_dev_nanos, samples: 100 })
+        }
+
+        fn baseline(timing: Timing) -> Baseline {
+            Baseline {
+                name: "before".into(),
+                run: RunInfo { timestamp: 1_703_226_600, commit: Some("2be414c".into()), host: None },
+                timings: Timings { data: vec![timing] },
+            }
+        }
+
+        #[test]
+        fn round_trips_baselines() {
+            let saved = baseline(timing("174.0ms", stats(174e6, 2e6)));
+            let json = tinyjson::JsonValue::from(&saved).stringify().unwrap();
+            let loaded = Baseline::try_from(json).unwrap();
+            assert_eq!(loaded.name, "before");
+            assert_eq!(loaded.run, saved.run);
+            assert_eq!(loaded.timings.data[0].stats_1, saved.timings.data[0].stats_1);
+        }
+
+        #[test]
+        fn rejects_invalid_names() {
+            assert!(Baseline::delete("../README").is_err());
+            assert!(Baseline::load("").is_err());
+        }
+
+        #[test]
+        fn compares_with_significance() {
+            let saved = baseline(timing("174.0ms", stats(174e6, 2e6)));
+            let faster = saved.compare(&Timings { data: vec![timing("150.0ms", stats(150e6, 2e6))] });
+            assert_eq!(faster.len(), 1);
+            assert!(faster[0].ratio() < 0.9);
+            assert_eq!(faster[0].is_significant(), Some(true));
+            assert!(!faster[0].is_regression(10.0));
+
+            let noisy = saved.compare(&Timings { data: vec![timing("200.0ms", stats(200e6, 200e6))] });
+            assert_eq!(noisy[0].is_significant(), Some(false));
+            assert!(!noisy[0].is_regression(10.0));
+
+            // timings stored before standard deviations were recorded count as a single sample.
+            let legacy = baseline(timing("100.0ms", None)).compare(&Timings { data: vec![timing("150.0ms", None)] });
+            assert_eq!(legacy[0].is_significant(), None);
+            assert!(legacy[0].is_regression(10.0));
+        }
+
+        #[test]
+        fn formats_comparison_table() {
+            let saved = baseline(timing("174.0ms", stats(174e6, 2e6)));
+            let comparisons = saved.compare(&Timings { data: vec![timing("150.0ms", stats(150e6, 2e6))] });
+            assert_eq!(
+                format_comparison_table(&comparisons).lines().last(),
+                Some("| 22 | 1 | `174.0ms ± 2.0ms` | `150.0ms ± 2.0ms` | 0.86x | yes (t = -84.9) |")
+            );
+        }
+    }
+
     mod is_day_complete {
         use crate::{
             day,
@@ -289,6 +823,12 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 }],
             };
 
@@ -303,6 +843,12 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 }],
             };
 
@@ -317,6 +863,12 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 }],
             };
 
@@ -341,6 +893,12 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 }],
             };
             let merged = timings.merge(&other);
@@ -361,6 +919,12 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    memory_1: None,
+                    memory_2: None,
+                    stats_1: None,
+                    stats_2: None,
+                    read_input: None,
+                    parse: None,
                 }],
             };
             let merged = timings.merge(&other);
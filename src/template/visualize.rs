@@ -0,0 +1,392 @@
+/// Frames emitted by a day's visualisation, played back as a terminal animation or exported as a GIF.
+use std::io::{self, stdout, IsTerminal, Read, Write};
+use std::process::{self, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use std::{env, fs, thread};
+
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+/// Implemented by a day to show its solution at work, enabled with
+/// `solution!(DAY, visualize = Type)` and played with `cargo visualize DAY`.
+pub trait Visualize {
+    fn frames(input: &str) -> Result<Vec<Frame>, String>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Default,
+    Gray,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+const PALETTE: [Color; 9] = [
+    Color::Default,
+    Color::Gray,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+impl Color {
+    fn ansi(self) -> &'static str {
+        match self {
+            Color::Default => "\x1b[39m",
+            Color::Gray => "\x1b[90m",
+            Color::Red => "\x1b[91m",
+            Color::Green => "\x1b[92m",
+            Color::Yellow => "\x1b[93m",
+            Color::Blue => "\x1b[94m",
+            Color::Magenta => "\x1b[95m",
+            Color::Cyan => "\x1b[96m",
+            Color::White => "\x1b[97m",
+        }
+    }
+
+    pub(crate) fn rgb(self) -> [u8; 3] {
+        match self {
+            Color::Default => [200, 200, 200],
+            Color::Gray => [90, 90, 90],
+            Color::Red => [230, 60, 60],
+            Color::Green => [60, 200, 80],
+            Color::Yellow => [240, 210, 60],
+            Color::Blue => [70, 120, 240],
+            Color::Magenta => [210, 80, 210],
+            Color::Cyan => [60, 210, 220],
+            Color::White => [255, 255, 255],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Color,
+}
+
+impl Cell {
+    /// Empty cells are drawn as background in exported images.
+    fn is_empty(self) -> bool {
+        self.color == Color::Default && matches!(self.glyph, ' ' | '.')
+    }
+}
+
+/// A grid of coloured glyphs, with a caption shown under it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    rows: usize,
+    cols: usize,
+    cells: Vec<Cell>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let blank = Cell { glyph: ' ', color: Color::Default };
+        Self { rows, cols, cells: vec![blank; rows * cols], caption: String::new() }
+    }
+
+    /// Builds a frame from lines of text, colouring each glyph with `color`.
+    pub fn from_text(text: &str, color: impl Fn(char) -> Color) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let cols = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let mut frame = Self::new(lines.len(), cols);
+        for (row, line) in lines.iter().enumerate() {
+            for (col, glyph) in line.chars().enumerate() {
+                frame.set(row, col, glyph, color(glyph));
+            }
+        }
+        frame
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, glyph: char, color: Color) {
+        if row < self.rows && col < self.cols {
+            self.cells[row * self.cols + col] = Cell { glyph, color };
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
+        (row < self.rows && col < self.cols).then(|| self.cells[row * self.cols + col])
+    }
+
+    fn render_ansi(&self) -> String {
+        let mut out = String::new();
+        for row in self.cells.chunks(self.cols.max(1)) {
+            let mut current = None;
+            for cell in row {
+                if current != Some(cell.color) {
+                    out.push_str(cell.color.ansi());
+                    current = Some(cell.color);
+                }
+                out.push(cell.glyph);
+            }
+            out.push_str(ANSI_RESET);
+            out.push_str("\x1b[K\n");
+        }
+        out
+    }
+}
+
+/// Entry point used by the `solution!` macro when a day is run with `--visualize`.
+pub fn run<V: Visualize>(input: &str) {
+    let frames = match V::frames(input) {
+        Ok(frames) if !frames.is_empty() => frames,
+        Ok(_) => {
+            eprintln!("the visualisation produced no frame.");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    let fps = arg_value("--fps").unwrap_or(10).max(1);
+
+    if let Some(path) = arg_value::<String>("--gif") {
+        let scale = arg_value("--scale").unwrap_or(4).max(1);
+        match fs::write(&path, encode_gif(&frames, scale, fps)) {
+            Ok(()) => println!("Wrote {} frames to {path}.", frames.len()),
+            Err(e) => {
+                eprintln!("could not write {path}: {e}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    play(&frames, fps);
+}
+
+fn arg_value<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == name)?;
+    args.get(index + 1)?.parse().ok()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Byte read for Ctrl+C once the terminal no longer turns it into `SIGINT`.
+const CTRL_C: u8 = 0x03;
+
+/// Puts the terminal in non-canonical mode so keys are read as soon as they are pressed.
+/// Signal keys are read as bytes too, so that Ctrl+C stops the player through the same path
+/// as `q` and the previous settings are restored when dropped.
+struct RawTerminal {
+    saved: String,
+}
+
+impl RawTerminal {
+    fn enable() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        let output = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let saved = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        let status = Command::new("stty").args(["-icanon", "-echo", "-isig", "min", "1"]).status().ok()?;
+        status.success().then_some(Self { saved })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = Command::new("stty").arg(&self.saved).status();
+    }
+}
+
+fn spawn_key_reader() -> Receiver<u8> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // keys are wanted one at a time, as soon as they are pressed.
+        let mut byte = [0];
+        while io::stdin().read(&mut byte).is_ok_and(|read| read == 1) {
+            if sender.send(byte[0]).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn play(frames: &[Frame], fps: u32) {
+    let terminal = RawTerminal::enable();
+    let keys = terminal.as_ref().map(|_| spawn_key_reader());
+    let mut stdout = stdout();
+    let mut delay = Duration::from_secs(1) / fps;
+    let mut index = 0;
+    let mut paused = false;
+
+    print!("\x1b[?25l\x1b[2J");
+    loop {
+        let frame = &frames[index];
+        let status = if paused { "paused" } else { "playing" };
+        print!("\x1b[H{}", frame.render_ansi());
+        println!("{ANSI_BOLD}{}/{}{ANSI_RESET} {}\x1b[K", index + 1, frames.len(), frame.caption);
+        if keys.is_some() {
+            println!("{status} · [space] play/pause · [n/b] step · [+/-] speed · [q] quit\x1b[K");
+        }
+        let _ = stdout.flush();
+
+        let key = match &keys {
+            Some(keys) if paused => keys.recv().ok(),
+            Some(keys) => match keys.recv_timeout(delay) {
+                Ok(key) => Some(key),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => Some(b'q'),
+            },
+            None => {
+                thread::sleep(delay);
+                None
+            }
+        };
+        match key {
+            Some(b' ') => paused = !paused,
+            Some(b'n' | b'l') => {
+                index = (index + 1).min(frames.len() - 1);
+                paused = true;
+            }
+            Some(b'b' | b'h') => {
+                index = index.saturating_sub(1);
+                paused = true;
+            }
+            Some(b'+') => delay = (delay / 2).max(Duration::from_millis(1)),
+            Some(b'-') => delay *= 2,
+            Some(b'q' | CTRL_C) => break,
+            Some(_) => {}
+            None if index + 1 < frames.len() => index += 1,
+            None if keys.is_some() => paused = true,
+            None => break,
+        }
+    }
+    print!("\x1b[?25h");
+    let _ = stdout.flush();
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Codes are kept 5 bits wide by clearing the LZW table before it grows past 32 entries.
+const LZW_MIN_CODE_SIZE: u8 = 4;
+const LZW_CLEAR: u16 = 1 << LZW_MIN_CODE_SIZE;
+const LZW_END: u16 = LZW_CLEAR + 1;
+const LZW_CODES_PER_CLEAR: usize = 12;
+
+/// Encodes the frames as a looping GIF, each cell drawn as a `scale`×`scale` square.
+/// Glyphs are not drawn, only the cell colours.
+pub fn encode_gif(frames: &[Frame], scale: usize, fps: u32) -> Vec<u8> {
+    let rows = frames.iter().map(|frame| frame.rows).max().unwrap_or(0);
+    let cols = frames.iter().map(|frame| frame.cols).max().unwrap_or(0);
+    let (width, height) = ((cols * scale).clamp(1, 0xFFFF), (rows * scale).clamp(1, 0xFFFF));
+    let delay = (100 / fps).max(1) as u16;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"GIF89a");
+    out.extend_from_slice(&(width as u16).to_le_bytes());
+    out.extend_from_slice(&(height as u16).to_le_bytes());
+    // global colour table of 2^(3 + 1) entries.
+    out.extend_from_slice(&[0xF3, 0, 0]);
+    for index in 0..16 {
+        let rgb = match index {
+            0 => [0, 0, 0],
+            i => PALETTE.get(i - 1).map_or([0, 0, 0], |color| color.rgb()),
+        };
+        out.extend_from_slice(&rgb);
+    }
+    out.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for frame in frames {
+        out.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        out.extend_from_slice(&delay.to_le_bytes());
+        out.extend_from_slice(&[0x00, 0x00, 0x2C, 0, 0, 0, 0]);
+        out.extend_from_slice(&(width as u16).to_le_bytes());
+        out.extend_from_slice(&(height as u16).to_le_bytes());
+        out.push(0x00);
+
+        let pixels = (0..height).flat_map(|y| {
+            (0..width).map(move |x| match frame.get(y / scale, x / scale) {
+                Some(cell) if !cell.is_empty() => PALETTE.iter().position(|&c| c == cell.color).unwrap_or(0) as u16 + 1,
+                _ => 0,
+            })
+        });
+        out.push(LZW_MIN_CODE_SIZE);
+        for block in lzw_uncompressed(pixels).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend_from_slice(block);
+        }
+        out.push(0x00);
+    }
+    out.push(0x3B);
+    out
+}
+
+/// LZW stream made only of literal codes, which every decoder accepts.
+fn lzw_uncompressed(pixels: impl Iterator<Item = u16>) -> Vec<u8> {
+    let code_size = u32::from(LZW_MIN_CODE_SIZE) + 1;
+    let mut bytes = Vec::new();
+    let (mut buffer, mut bits) = (0_u32, 0);
+    let mut push = |code: u16| {
+        buffer |= u32::from(code) << bits;
+        bits += code_size;
+        while bits >= 8 {
+            bytes.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+    };
+    for (index, pixel) in pixels.enumerate() {
+        if index % LZW_CODES_PER_CLEAR == 0 {
+            push(LZW_CLEAR);
+        }
+        push(pixel);
+    }
+    push(LZW_END);
+    if bits > 0 {
+        bytes.push(buffer as u8);
+    }
+    bytes
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_from_text() {
+        let frame = Frame::from_text("#.\n.O", |c| if c == 'O' { Color::Yellow } else { Color::Default });
+        assert_eq!(frame.get(1, 1), Some(Cell { glyph: 'O', color: Color::Yellow }));
+        assert_eq!(frame.get(2, 0), None);
+        assert_eq!(frame.render_ansi().lines().count(), 2);
+    }
+
+    #[test]
+    fn lzw_packs_five_bit_codes() {
+        // clear (16), 1, 2, end (17): 0b10001_00010_00001_10000 packed from the low bits.
+        assert_eq!(lzw_uncompressed([1, 2].into_iter()), vec![0x30, 0x88, 0x08]);
+    }
+
+    #[test]
+    fn gif_structure() {
+        let frames = vec![Frame::from_text("#.", |_| Color::Red); 2];
+        let gif = encode_gif(&frames, 2, 10);
+        assert!(gif.starts_with(b"GIF89a\x04\x00\x02\x00"));
+        assert_eq!(gif.last(), Some(&0x3B));
+        assert_eq!(gif.windows(3).filter(|w| w == &[0x21, 0xF9, 0x04]).count(), 2);
+    }
+}
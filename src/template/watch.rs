@@ -0,0 +1,317 @@
+/// Polls the files of a day for `cargo watch`, and sums up each run of its tests and solution.
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::template::config::Config;
+use crate::template::readme_benchmarks::get_path_for_bin;
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+/// Number of compiler error lines shown when the day doesn't build.
+const MAX_ERROR_LINES: usize = 12;
+
+/// Source of the day, the shared library modules, and the example and input files of the day.
+pub fn watched_paths(day: Day) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(get_path_for_bin(day))];
+    if let Ok(entries) = fs::read_dir("src") {
+        let mut shared: Vec<PathBuf> = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|e| e == "rs") && !path.ends_with("main.rs"))
+            .collect();
+        shared.sort();
+        paths.extend(shared);
+    }
+    let paths_config = &Config::get().paths;
+    for name in [format!("{day}.txt"), format!("{day}-1.txt"), format!("{day}-2.txt")] {
+        paths.push(paths_config.examples.join(name));
+    }
+    paths.push(paths_config.inputs.join(format!("{day}.txt")));
+    paths
+}
+
+/// Modification time and size of a file, `None` while it doesn't exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Detects changes by comparing the stamps of the watched files between polls.
+pub struct Watcher {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Watcher { files: paths.into_iter().map(|path| { let stamp = stamp(&path); (path, stamp) }).collect() }
+    }
+
+    /// Files created, changed or deleted since the last poll.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = vec![];
+        for (path, last) in &mut self.files {
+            let current = stamp(path);
+            if current != *last {
+                *last = current;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+/// Waits for a burst of saves to settle before running anything.
+pub struct Debounce {
+    delay: Duration,
+    pending_since: Option<Instant>,
+}
+
+impl Debounce {
+    pub fn new(delay: Duration) -> Self {
+        Debounce { delay, pending_since: None }
+    }
+
+    /// Called after each poll. Returns `true` once, when no change came in for `delay` after the last one.
+    pub fn update(&mut self, changed: bool, now: Instant) -> bool {
+        if changed {
+            self.pending_since = Some(now);
+            return false;
+        }
+        match self.pending_since {
+            Some(since) if now.duration_since(since) >= self.delay => {
+                self.pending_since = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tests {
+    /// The day didn't compile, with the first lines of the errors.
+    BuildFailed(Vec<String>),
+    Ran { passed: usize, failures: Vec<String> },
+}
+
+/// A part as printed by the runner, e.g. `1320 (2.5µs)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartOutput {
+    pub part: u8,
+    pub answer: String,
+}
+
+/// Outcome of a run of the example tests, then of the solution on the real input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub day: Day,
+    pub run: usize,
+    pub changed: Vec<PathBuf>,
+    pub tests: Tests,
+    pub parts: Vec<PartOutput>,
+    /// Last line the solution wrote to stderr, e.g. a missing input.
+    pub error: Option<String>,
+}
+
+/// Runs the tests of `day`, and its solution if it builds.
+pub fn run(day: Day, release: bool, run: usize, changed: Vec<PathBuf>) -> Report {
+    let bin = day.to_string();
+    let mut run_args = vec!["run", "--quiet", "--bin", &bin];
+    if release {
+        run_args.push("--release");
+    }
+
+    let tests = run_tests(day, release);
+    let (parts, error) = match (&tests, cargo(&run_args)) {
+        (Tests::Ran { .. }, Some(output)) => (parse_parts(&lines(&output.stdout)), lines(&output.stderr).pop()),
+        _ => (vec![], None),
+    };
+
+    Report { day, run, changed, tests, parts, error }
+}
+
+/// Runs `cargo test --bin DD` on the examples of `day`.
+pub fn run_tests(day: Day, release: bool) -> Tests {
+    let bin = day.to_string();
+    let mut args = vec!["test", "--bin", &bin];
+    if release {
+        args.push("--release");
+    }
+    match cargo(&args) {
+        Some(output) => parse_tests(&lines(&output.stdout), &lines(&output.stderr)),
+        None => Tests::BuildFailed(vec!["could not run cargo.".into()]),
+    }
+}
+
+fn cargo(args: &[&str]) -> Option<Output> {
+    Command::new("cargo").args(args).output().ok()
+}
+
+fn lines(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes).lines().map(strip_ansi).collect()
+}
+
+/// The text of a line without colour codes, and only what follows its last carriage return.
+fn strip_ansi(line: &str) -> String {
+    let line = line.rsplit('\r').next().unwrap_or_default();
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+/// Reads the harness output, e.g. `test tests::test_part_one ... FAILED` and `test result: ok. 3 passed; 0 failed`.
+pub fn parse_tests(stdout: &[String], stderr: &[String]) -> Tests {
+    let passed: Option<usize> = stdout
+        .iter()
+        .filter_map(|line| line.strip_prefix("test result: "))
+        .filter_map(|line| line.split(". ").nth(1)?.split(' ').next()?.parse::<usize>().ok())
+        .reduce(|a, b| a + b);
+    let Some(passed) = passed else {
+        let errors = stderr
+            .iter()
+            .skip_while(|line| !line.starts_with("error"))
+            .take(MAX_ERROR_LINES)
+            .cloned()
+            .collect();
+        return Tests::BuildFailed(errors);
+    };
+    let failures = stdout
+        .iter()
+        .filter_map(|line| line.strip_prefix("test ")?.strip_suffix(" ... FAILED"))
+        .map(String::from)
+        .collect();
+    Tests::Ran { passed, failures }
+}
+
+/// Reads the lines of the runner, e.g. `Part 1: 1320 (2.5µs)`.
+pub fn parse_parts(stdout: &[String]) -> Vec<PartOutput> {
+    stdout
+        .iter()
+        .filter_map(|line| {
+            let (name, answer) = line.split_once(": ")?;
+            let part = name.strip_prefix("Part ")?.parse().ok()?;
+            Some(PartOutput { part, answer: answer.trim().to_string() })
+        })
+        .collect()
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let changed: Vec<String> = self.changed.iter().map(|path| path.display().to_string()).collect();
+        write!(f, "{ANSI_BOLD}Day {}{ANSI_RESET} · run {}", self.day, self.run)?;
+        if !changed.is_empty() {
+            write!(f, " · changed {}", changed.join(", "))?;
+        }
+        writeln!(f)?;
+
+        match &self.tests {
+            Tests::BuildFailed(errors) => {
+                writeln!(f, "Tests   ✖ build failed")?;
+                for line in errors {
+                    writeln!(f, "        {line}")?;
+                }
+            }
+            Tests::Ran { passed, failures } if failures.is_empty() => writeln!(f, "Tests   ✔ {passed} passed")?,
+            Tests::Ran { passed, failures } => {
+                writeln!(f, "Tests   ✖ {} of {} failed: {}", failures.len(), passed + failures.len(), failures.join(", "))?;
+            }
+        }
+        for part in &self.parts {
+            writeln!(f, "Part {}  {}", part.part, part.answer)?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "Error   {error}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::day;
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| strip_ansi(line)).collect()
+    }
+
+    #[test]
+    fn detects_changed_files() {
+        let path = std::env::temp_dir().join(format!("aoc-watch-{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut watcher = Watcher::new(vec![path.clone()]);
+        assert!(watcher.poll().is_empty());
+        fs::write(&path, "1").unwrap();
+        assert_eq!(watcher.poll(), [path.clone()]);
+        fs::write(&path, "12").unwrap();
+        assert_eq!(watcher.poll(), [path.clone()]);
+        assert!(watcher.poll().is_empty());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(watcher.poll(), [path]);
+    }
+
+    #[test]
+    fn debounces_bursts() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut debounce = Debounce::new(Duration::from_millis(300));
+        assert!(!debounce.update(false, at(0)));
+        assert!(!debounce.update(true, at(0)));
+        assert!(!debounce.update(true, at(200)));
+        assert!(!debounce.update(false, at(400)));
+        assert!(debounce.update(false, at(500)));
+        assert!(!debounce.update(false, at(900)));
+    }
+
+    #[test]
+    fn parses_test_results() {
+        let stdout = strings(&[
+            "running 3 tests",
+            "test tests::test_part_one ... ok",
+            "test tests::test_part_two ... FAILED",
+            "test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out",
+        ]);
+        assert_eq!(
+            parse_tests(&stdout, &[]),
+            Tests::Ran { passed: 2, failures: vec!["tests::test_part_two".into()] }
+        );
+
+        let stderr = strings(&["   Compiling advent_of_code v0.10.0", "error[E0425]: cannot find value `x`", "  --> src/bin/15.rs:3:5"]);
+        assert_eq!(
+            parse_tests(&[], &stderr),
+            Tests::BuildFailed(vec!["error[E0425]: cannot find value `x`".into(), "  --> src/bin/15.rs:3:5".into()])
+        );
+    }
+
+    #[test]
+    fn writes_panel() {
+        let stdout = strings(&[
+            "Part 1: \x1b[1m1320\x1b[0m\rPart 1: \x1b[1m1320\x1b[0m (2.5µs)",
+            "Part 2: ✖             ",
+        ]);
+        let report = Report {
+            day: day!(15),
+            run: 2,
+            changed: vec![PathBuf::from("src/bin/15.rs")],
+            tests: Tests::Ran { passed: 3, failures: vec![] },
+            parts: parse_parts(&stdout),
+            error: Some("could not open data/inputs/15.txt".into()),
+        };
+        assert_eq!(
+            report.to_string(),
+            format!("{ANSI_BOLD}Day 15{ANSI_RESET} · run 2 · changed src/bin/15.rs\nTests   ✔ 3 passed\nPart 1  1320 (2.5µs)\nPart 2  ✖\nError   could not open data/inputs/15.txt\n")
+        );
+    }
+}